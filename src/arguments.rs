@@ -1,23 +1,19 @@
 use clap::{App, arg_enum, clap_app};
 
-arg_enum! {
-    #[derive(PartialEq, Debug)]
-    pub enum Types {
-        Bug,
-        Feature,
-        Improvement,
-        Task,
-    }
-}
+use super::links::LINK_TYPES;
+use super::output::FORMATS;
+use super::settings::KEYS;
 
-arg_enum! {
-    #[derive(PartialEq, Debug)]
-    pub enum Priority {
-        Low,
-        Medium,
-        High,
+/// Keys accepted by config get, set and unset
+const SETTINGS: [&str; KEYS.len()] = {
+    let mut keys = [""; KEYS.len()];
+    let mut i = 0;
+    while i < KEYS.len() {
+        keys[i] = KEYS[i].0;
+        i += 1;
     }
-}
+    keys
+};
 
 arg_enum! {
     #[derive(PartialEq, Debug)]
@@ -38,17 +34,35 @@ pub fn init<'a, 'b>() -> App<'a, 'b> {
 }
 
 pub fn list<'a, 'b>() -> App<'a, 'b> {
-    clap_app!(list =>
+    filters(clap_app!(list =>
         (about: "Displays issues")
+        (@arg sort: -S --sort +takes_value "Sort listing by comma separated fields with optional direction, e.g. 'priority:desc,due'. Fields: hash, title, type, parent, author, created, assigned, start, due, status, priority, progress, labels")
+        (@arg table: -l --table "Display listing as compact table")
+        (@arg columns: --columns +takes_value requires[table] "Comma separated table columns. Default is 'hash,type,status,priority,progress,due,assigned,title'")
+        (@arg format: -f --format +takes_value possible_values(FORMATS) conflicts_with[table] "Set output format. Default is text")
+    ))
+}
+
+pub fn tree<'a, 'b>() -> App<'a, 'b> {
+    filters(clap_app!(tree =>
+        (about: "Displays parent/child hierarchy of issues")
+        (@arg depth: -L --depth +takes_value "Limit displayed hierarchy to given depth")
+        (@arg root: "Hash of the issue to start the hierarchy from")
+    ))
+}
+
+/// Adds the issue filter arguments shared by all listing subcommands
+fn filters<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+    clap_app!(@app (app)
         (@arg hash: -h --hash +takes_value "Filter listing by issue hash")
-        (@arg type: -t --type +takes_value possible_values(&Types::variants()) "Filter listing by issue type")
+        (@arg type: -t --type +takes_value "Filter listing by issue type")
         (@arg parent: -p --parent +takes_value "Filter listing by parent issue hash")
         (@arg author: -a --author +takes_value "Filter listing by issue author")
         (@arg from_creation: -c --from_creation +takes_value "Filter listing from given issue creation date")
         (@arg to_creation: -C --to_creation +takes_value "Filter listing to given issue creation date")
         (@arg title: -T --title +takes_value "Filter listing by issue title regex")
         (@arg description: -d --description +takes_value "Filter listing by issue description regex")
-        (@arg priority: -i --priority +takes_value possible_values(&Priority::variants()) "Filter listing by issue priority")
+        (@arg priority: -i --priority +takes_value "Filter listing by issue priority")
         (@arg status: -s --status +takes_value possible_values(&Status::variants()) "Filter listing by issue status")
         (@arg assigned_to: -r --assigned +takes_value "Filter listing by issue assigned to regex")
         (@arg from_start: -b --from_start +takes_value "Filter listing from given issue start date")
@@ -57,22 +71,33 @@ pub fn list<'a, 'b>() -> App<'a, 'b> {
         (@arg to_due: -E --to_due +takes_value "Filter listing to given due date")
         (@arg from_progress: -g --from_progress +takes_value "Filter listing from given issue progress")
         (@arg to_progress: -G --to_progress +takes_value "Filter listing to given issue progress")
+        (@arg milestone: -m --milestone +takes_value "Filter listing by milestone")
+        (@arg tag: --tag +takes_value +multiple number_of_values(1) "Filter listing by issue label. Can be given multiple times")
+        (@arg field: --field +takes_value +multiple number_of_values(1) "Filter listing by custom field, e.g. 'component=parser'. Can be given multiple times")
+        (@arg blocked: --blocked conflicts_with[unblocked] "Filter listing by issues blocked by an open issue")
+        (@arg unblocked: --unblocked "Filter listing by issues not blocked by an open issue")
+        (@arg query: -q --query +takes_value "Filter listing by query expression, e.g. '(status:open or status:in_progress) and not assigned:bob and due<2026-11-01'")
     )
 }
 
 pub fn open<'a, 'b>() -> App<'a, 'b> {
     clap_app!(open =>
         (about: "Opens an issues")
-        (@arg type: -t --type +takes_value possible_values(&Types::variants()) "Set issue type. Default is bug")
+        (@arg type: -t --type +takes_value "Set issue type as declared in the tracker config. Default is bug or the configured default")
         (@arg parent: -p --parent +takes_value "Set issue parent hash")
         (@arg author: -a --author +takes_value "Set issue author. Default is the configured/current user")
         (@arg description_edit: -D --description_edit "Open editor for issue description editing")
-        (@arg priority: -i --priority +takes_value possible_values(&Priority::variants()) "Set issue priority. Default is medium")
+        (@arg priority: -i --priority +takes_value "Set issue priority as declared in the tracker config. Default is medium or the configured default")
         (@arg status: -s --status +takes_value possible_values(&Status::variants()) "Set issue status. Default is open")
         (@arg assigned_to: -r --assigned +takes_value "Assign issue to given user")
         (@arg start_date: -b --start_date +takes_value "Set issue start date. Default is the current date time")
-        (@arg due_date: -e --due_date +takes_value "Set issue due date")
+        (@arg due_date: -e --due_date +takes_value "Set issue due date, e.g. 2026-10-17, 2026-10-17 14:30, tomorrow, +3d, next friday or end of month")
         (@arg progress: -g --progress +takes_value "Set issue progress")
+        (@arg estimate: --estimate +takes_value "Set estimated time, e.g. 1h30m. A day counts 8 hours, 0 removes the estimate")
+        (@arg rollup: --rollup +takes_value possible_values(&["on", "off"]) "Derive issue progress from its children")
+        (@arg milestone: -m --milestone +takes_value "Assign issue to milestone. An empty value removes the milestone")
+        (@arg tag: --tag +takes_value +multiple number_of_values(1) "Add label to issue. Can be given multiple times")
+        (@arg field: --field +takes_value +multiple number_of_values(1) "Set custom field declared in the tracker config, e.g. 'component=parser'. An empty value unsets the field. Can be given multiple times")
         (@arg title: +required "Set title of the issue")
     )
 }
@@ -80,17 +105,23 @@ pub fn open<'a, 'b>() -> App<'a, 'b> {
 pub fn edit<'a, 'b>() -> App<'a, 'b> {
     clap_app!(edit =>
         (about: "Edit an existing issues")
-        (@arg type: -t --type +takes_value possible_values(&Types::variants()) "Set issue type")
+        (@arg type: -t --type +takes_value "Set issue type as declared in the tracker config")
         (@arg parent: -p --parent +takes_value "Set issue parent hash")
         (@arg author: -a --author +takes_value "Set issue author. Default is the configured/current user")
         (@arg title: -T --title +takes_value "Set title of the issue")
         (@arg description_edit: -D --description_edit "Open editor for issue description editing")
-        (@arg priority: -i --priority +takes_value possible_values(&Priority::variants()) "Set issue priority")
+        (@arg priority: -i --priority +takes_value "Set issue priority as declared in the tracker config")
         (@arg status: -s --status +takes_value possible_values(&Status::variants()) "Set issue status")
         (@arg assigned_to: -r --assigned +takes_value "Assign issue to given user")
         (@arg start_date: -b --start_date +takes_value "Set issue start date. Default is the current date time")
-        (@arg due_date: -e --due_date +takes_value "Set issue due date")
+        (@arg due_date: -e --due_date +takes_value "Set issue due date, e.g. 2026-10-17, 2026-10-17 14:30, tomorrow, +3d, next friday or end of month")
         (@arg progress: -g --progress +takes_value "Set issue progress")
+        (@arg estimate: --estimate +takes_value "Set estimated time, e.g. 1h30m. A day counts 8 hours, 0 removes the estimate")
+        (@arg rollup: --rollup +takes_value possible_values(&["on", "off"]) "Derive issue progress from its children")
+        (@arg milestone: -m --milestone +takes_value "Assign issue to milestone. An empty value removes the milestone")
+        (@arg tag: --tag +takes_value +multiple number_of_values(1) "Add label to issue. Can be given multiple times")
+        (@arg field: --field +takes_value +multiple number_of_values(1) "Set custom field declared in the tracker config, e.g. 'component=parser'. An empty value unsets the field. Can be given multiple times")
+        (@arg untag: --untag +takes_value +multiple number_of_values(1) "Remove label from issue. Can be given multiple times")
         (@arg hash: +required "Hash of the issue to be edited")
     )
 }
@@ -102,6 +133,13 @@ pub fn close<'a, 'b>() -> App<'a, 'b> {
     )
 }
 
+pub fn reopen<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(reopen =>
+        (about: "Reopen a closed or rejected issue")
+        (@arg hash: +required "Hash of the issue to be reopened")
+    )
+}
+
 pub fn remove<'a, 'b>() -> App<'a, 'b> {
     clap_app!(remove =>
         (about: "Remove an existing issue")
@@ -109,9 +147,226 @@ pub fn remove<'a, 'b>() -> App<'a, 'b> {
     )
 }
 
+pub fn comment<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(comment =>
+        (about: "Comment on an existing issue")
+        (@arg author: -a --author +takes_value "Set comment author. Default is the configured/current user")
+        (@arg hash: +required "Hash of the issue to be commented")
+    )
+}
+
+pub fn history<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(history =>
+        (about: "Show change history of an existing issue")
+        (@arg hash: +required "Hash of the issue")
+    )
+}
+
+pub fn sync_commits<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(sync_commits =>
+        (about: "Close issues referenced by closing keywords in git commit messages, e.g. 'Fixes <hash>'")
+        (@arg dry_run: -n --dry_run "Show issues which would be closed without writing issues")
+        (@arg range: "Git revision range to scan. Default is HEAD")
+    )
+    .name("sync-commits")
+}
+
+pub fn hooks<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(hooks =>
+        (about: "Manage git hooks validating issue references in commit messages")
+        (@setting SubcommandRequiredElseHelp)
+        (@subcommand install =>
+            (about: "Install commit-msg hook rejecting references to unknown, closed or rejected issues")
+            (@arg pre_push: -p --pre_push "Also install pre-push hook checking all pushed commits")
+            (@arg force: -f --force "Overwrite existing hooks")
+        )
+    )
+    .subcommand(
+        clap_app!(check_message =>
+            (about: "Check issue references of a commit message file")
+            (@setting Hidden)
+            (@arg file: +required "Path of the commit message file")
+        )
+        .name("check-message"),
+    )
+    .subcommand(
+        clap_app!(check_push =>
+            (about: "Check issue references of pushed commits read from stdin")
+            (@setting Hidden)
+        )
+        .name("check-push"),
+    )
+}
+
+pub fn milestone<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(milestone =>
+        (about: "Manage milestones. Lists milestones without subcommand")
+        (@subcommand list =>
+            (about: "List milestones with issue counts, aggregate progress and overdue status")
+        )
+        (@subcommand add =>
+            (about: "Add a milestone")
+            (@arg description: -d --description +takes_value "Set milestone description")
+            (@arg target_date: -e --target_date +takes_value "Set milestone target date")
+            (@arg name: +required "Name of the milestone")
+        )
+        (@subcommand edit =>
+            (about: "Edit an existing milestone")
+            (@arg rename: -n --rename +takes_value "Rename milestone, assigned issues are moved along")
+            (@arg description: -d --description +takes_value "Set milestone description. An empty value removes it")
+            (@arg target_date: -e --target_date +takes_value "Set milestone target date. An empty value removes it")
+            (@arg name: +required "Name of the milestone")
+        )
+        (@subcommand remove =>
+            (about: "Remove a milestone")
+            (@arg force: -f --force "Remove milestone from assigned issues instead of failing")
+            (@arg name: +required "Name of the milestone")
+        )
+    )
+}
+
+pub fn link<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(link =>
+        (about: "Link an existing issue to another issue. Dependency cycles are rejected")
+        (@arg hash: +required "Hash of the issue")
+        (@arg kind: +required possible_values(LINK_TYPES) "Type of the link")
+        (@arg target: +required "Hash of the linked issue")
+    )
+}
+
+pub fn unlink<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(unlink =>
+        (about: "Remove a link between two issues")
+        (@arg hash: +required "Hash of the issue")
+        (@arg kind: +required possible_values(LINK_TYPES) "Type of the link")
+        (@arg target: +required "Hash of the linked issue")
+    )
+}
+
+pub fn search<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(search =>
+        (about: "Search titles, descriptions and comments, e.g. 'crash \"null pointer\" pars*'")
+        (@arg limit: -l --limit +takes_value "Maximum number of shown results. Default is 10")
+        (@arg terms: +required +multiple "Search terms, double quoted phrases and prefixes ending with *")
+    )
+}
+
+pub fn stats<'a, 'b>() -> App<'a, 'b> {
+    filters(clap_app!(stats =>
+        (about: "Report issue counts, lead and cycle times and weekly throughput")
+        (@arg weeks: -w --weeks +takes_value "Number of weeks of throughput, at most 1040. Default is 12")
+        (@arg format: -f --format +takes_value possible_values(FORMATS) "Set output format. Default is text")
+    ))
+}
+
+pub fn board<'a, 'b>() -> App<'a, 'b> {
+    filters(clap_app!(board =>
+        (about: "Show issues as kanban board with a column per status and work in progress limits")
+    ))
+}
+
+pub fn tui<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(tui =>
+        (about: "Browse, filter and triage issues interactively. Press ? for key bindings")
+    )
+}
+
+pub fn attach<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(attach =>
+        (about: "Attach a file, e.g. a log, screenshot or patch, to an existing issue")
+        (@arg name: -n --name +takes_value "Set attachment name. Default is the file name")
+        (@arg hash: +required "Hash of the issue")
+        (@arg file: +required "Path of the file")
+    )
+}
+
+pub fn detach<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(detach =>
+        (about: "Remove an attachment from an existing issue")
+        (@arg hash: +required "Hash of the issue")
+        (@arg name: +required "Name of the attachment")
+    )
+}
+
+pub fn log<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(log =>
+        (about: "Log time worked on an existing issue")
+        (@arg author: -a --author +takes_value "Set work log author. Default is the configured/current user")
+        (@arg date: -d --date +takes_value "Set date of the work. Default is now")
+        (@arg hash: +required "Hash of the issue")
+        (@arg duration: +required "Time worked, e.g. 1h30m. A day counts 8 hours")
+    )
+}
+
+pub fn timer<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(timer =>
+        (about: "Track time worked on an issue with a running timer")
+        (@setting SubcommandRequiredElseHelp)
+        (@subcommand start =>
+            (about: "Start timer for an issue")
+            (@arg hash: +required "Hash of the issue")
+        )
+        (@subcommand stop =>
+            (about: "Stop running timer and log the elapsed time")
+            (@arg hash: "Hash of the issue, must match the running timer")
+        )
+        (@subcommand status =>
+            (about: "Show running timer")
+        )
+    )
+}
+
+pub fn time<'a, 'b>() -> App<'a, 'b> {
+    filters(clap_app!(time =>
+        (about: "Report logged and estimated time by issue and assignee, including child issues")
+        (@arg since: --since +takes_value "Only count time logged from given date")
+        (@arg until: --until +takes_value "Only count time logged to given date")
+    ))
+}
+
+pub fn export<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(export =>
+        (about: "Export all issues as JSON archive to stdout")
+    )
+}
+
+pub fn import<'a, 'b>() -> App<'a, 'b> {
+    clap_app!(import =>
+        (about: "Import issues from JSON archive")
+        (@arg dry_run: -n --dry_run "Show what would be imported without writing issues")
+        (@arg on_conflict: -c --on_conflict +takes_value possible_values(&["skip", "overwrite", "fail"]) "Set handling of already existing issue hashes. Default is fail")
+        (@arg file: +required "Path of the archive, - reads from stdin")
+    )
+}
+
 pub fn config<'a, 'b>() -> App<'a, 'b> {
     clap_app!(config =>
-        (about: "Show bugtracker configuration")
+        (about: "Show and change configuration")
+        (@arg format: -f --format +takes_value possible_values(FORMATS) "Set output format. Default is text")
+        (@subcommand list =>
+            (about: "Show bugtracker configuration and settings. Settings are layered global (~/.config/cobweb/config), repository and environment (COBWEB_<KEY>)")
+            (@arg format: -f --format +takes_value possible_values(FORMATS) "Set output format. Default is text")
+        )
+        (@subcommand get =>
+            (about: "Print effective value of a setting, exits with 1 if unset")
+            (@arg origin: -o --origin "Print layer the value comes from")
+            (@arg key: +required possible_values(&SETTINGS) "Setting key")
+        )
+        (@subcommand set =>
+            (about: "Set a setting")
+            (@arg global: -g --global "Write global instead of repository setting")
+            (@arg key: +required possible_values(&SETTINGS) "Setting key")
+            (@arg value: +required "Setting value")
+        )
+        (@subcommand unset =>
+            (about: "Unset a setting")
+            (@arg global: -g --global "Write global instead of repository setting")
+            (@arg key: +required possible_values(&SETTINGS) "Setting key")
+        )
+        (@subcommand edit =>
+            (about: "Edit settings in an editor")
+            (@arg global: -g --global "Edit global instead of repository settings")
+        )
     )
 }
 
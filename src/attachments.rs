@@ -0,0 +1,85 @@
+use std::collections::BTreeSet;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha2::{Digest, Sha256};
+
+/// Name of the directory in the tracker directory holding attachment contents by digest
+const ATTACHMENTS_DIR: &str = "attachments";
+
+/// Path of the stored content of an attachment
+pub fn path(tracker_dir: &Path, digest: &str) -> PathBuf {
+    tracker_dir.join(ATTACHMENTS_DIR).join(digest)
+}
+
+/// Copies file into the attachments directory and returns the SHA-256 digest and size of its
+/// content, identical contents are stored once
+pub fn store(tracker_dir: &Path, file: &Path) -> Result<(String, u64), String> {
+    let data = fs::read(file).map_err(|e| format!("{}: {}", file.display(), e))?;
+    let digest = store_data(tracker_dir, &data)?;
+    Ok((digest, data.len() as u64))
+}
+
+/// Stores data in the attachments directory and returns its SHA-256 digest
+pub fn store_data(tracker_dir: &Path, data: &[u8]) -> Result<String, String> {
+    let digest = digest(data);
+
+    let target = path(tracker_dir, &digest);
+    if let Some(dir) = target.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    if !target.exists() {
+        fs::write(&target, data).map_err(|e| format!("{}: {}", target.display(), e))?;
+    }
+    Ok(digest)
+}
+
+/// SHA-256 digest of data as stored in attachments
+pub fn digest(data: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(data))
+}
+
+/// Reads the stored content of an attachment
+pub fn read(tracker_dir: &Path, digest: &str) -> Result<Vec<u8>, String> {
+    let path = path(tracker_dir, digest);
+    fs::read(&path).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Removes stored contents whose digest isn't in referenced and returns their digests
+pub fn clean(tracker_dir: &Path, referenced: &BTreeSet<String>) -> Result<Vec<String>, String> {
+    let dir = tracker_dir.join(ATTACHMENTS_DIR);
+    let entries = match fs::read_dir(&dir) {
+        Ok(v) => v,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(vec![]),
+        Err(e) => return Err(format!("{}: {}", dir.display(), e)),
+    };
+
+    let mut removed = Vec::new();
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("{}: {}", dir.display(), e))?;
+        let digest = entry.file_name().to_string_lossy().to_string();
+        if referenced.contains(&digest) {
+            continue;
+        }
+        let path = entry.path();
+        fs::remove_file(&path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        removed.push(digest);
+    }
+    Ok(removed)
+}
+
+/// Formats bytes with binary units, e.g. '12.3 KiB'
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KiB", "MiB", "GiB", "TiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", size, UNITS[unit])
+}
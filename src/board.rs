@@ -0,0 +1,84 @@
+use std::str::FromStr;
+
+use super::{Issue, IssueStatus};
+
+/// Column order of boards without configured board_columns
+pub const DEFAULT_COLUMNS: &str = "Open, InProgress, Review, Halted, Closed";
+
+/// Board column of a status with its issues and work in progress limit
+pub struct Column<'a> {
+    pub status: IssueStatus,
+    pub limit: Option<usize>,
+    pub issues: Vec<&'a Issue>,
+}
+
+impl<'a> Column<'a> {
+    pub fn over_limit(&self) -> bool {
+        match self.limit {
+            Some(v) => self.issues.len() > v,
+            None => false,
+        }
+    }
+}
+
+/// Parses a comma separated column order, e.g. 'Open, InProgress, Review'
+pub fn columns(value: &str) -> Result<Vec<IssueStatus>, String> {
+    let mut columns: Vec<IssueStatus> = Vec::new();
+    for v in value.split(',').map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        let status = status(v)?;
+        if columns.contains(&status) {
+            return Err(format!("column {} given twice", v));
+        }
+        columns.push(status);
+    }
+    if columns.is_empty() {
+        return Err("no columns given".to_string());
+    }
+    Ok(columns)
+}
+
+/// Parses comma separated work in progress limits, e.g. 'InProgress=3, Review=2'
+pub fn wip_limits(value: &str) -> Result<Vec<(IssueStatus, usize)>, String> {
+    let mut limits = Vec::new();
+    for v in value.split(',').map(|v| v.trim()).filter(|v| !v.is_empty()) {
+        let mut parts = v.splitn(2, '=');
+        let name = parts.next().unwrap_or_default().trim();
+        let limit = match parts.next().map(|v| v.trim().parse::<usize>()) {
+            Some(Ok(v)) => v,
+            _ => return Err(format!("invalid limit '{}', expected e.g. InProgress=3", v)),
+        };
+        limits.push((status(name)?, limit));
+    }
+    Ok(limits)
+}
+
+fn status(v: &str) -> Result<IssueStatus, String> {
+    IssueStatus::from_str(v).map_err(|e| format!("invalid status '{}': {}", v, e))
+}
+
+/// Sorts issues into the columns, issues of statuses without a column are left out
+pub fn build<'a>(
+    statuses: Vec<IssueStatus>,
+    limits: &[(IssueStatus, usize)],
+    issues: &[&'a Issue],
+) -> Vec<Column<'a>> {
+    statuses
+        .into_iter()
+        .map(|status| {
+            let limit = limits
+                .iter()
+                .find(|(v, _)| *v == status)
+                .map(|(_, limit)| *limit);
+            let issues = issues
+                .iter()
+                .filter(|issue| *issue.status() == status)
+                .cloned()
+                .collect();
+            Column {
+                status,
+                limit,
+                issues,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,212 @@
+use std::path::PathBuf;
+use std::process;
+
+use super::IssueStatus;
+
+/// Keywords which close the issues referenced after them, e.g. "Fixes deadbeefdeadbeef"
+const CLOSING_KEYWORDS: &[&str] = &[
+    "close", "closes", "closed", "fix", "fixes", "fixed", "resolve", "resolves", "resolved",
+];
+
+/// Keywords which reference the issues after them without closing them
+const REFERENCE_KEYWORDS: &[&str] = &["ref", "refs", "references", "see", "issue", "issues"];
+
+/// Length of full issue hashes, e.g. deadbeefdeadbeef. Shorter words aren't references,
+/// otherwise words like "dead" or "2024" in prose or "#12" of other trackers would count.
+const HASH_LEN: usize = 16;
+
+pub struct Commit {
+    pub id: String,
+    pub author: String,
+    pub message: String,
+}
+
+/// Reads commits of the given revision range, oldest first
+pub fn log(working_dir: &PathBuf, range: &[&str]) -> Result<Vec<Commit>, String> {
+    let output = process::Command::new("git")
+        .arg("log")
+        .arg("--reverse")
+        .arg("--format=%H%x1f%an%x1f%B%x1e")
+        .args(range)
+        .arg("--")
+        .current_dir(working_dir)
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let commits = stdout
+        .split('\u{1e}')
+        .filter_map(|record| {
+            let mut fields = record.trim_start_matches('\n').splitn(3, '\u{1f}');
+            let id = fields.next()?.to_string();
+            let author = fields.next()?.to_string();
+            let message = fields.next()?.to_string();
+            Some(Commit {
+                id,
+                author,
+                message,
+            })
+        })
+        .collect();
+    Ok(commits)
+}
+
+/// Returns all full length hexadecimal words following a closing keyword, optionally marked
+/// with #, e.g. "Fixes deadbeefdeadbeef, #deadbeefdeadbee0 and deadbeefdeadbee1"
+pub fn closing_references(message: &str) -> Vec<String> {
+    keyword_references(message, CLOSING_KEYWORDS)
+}
+
+/// Returns all hexadecimal words following a closing or referencing keyword,
+/// e.g. "Refs deadbeefdeadbeef"
+pub fn references(message: &str) -> Vec<String> {
+    let mut keywords = CLOSING_KEYWORDS.to_vec();
+    keywords.extend_from_slice(REFERENCE_KEYWORDS);
+    keyword_references(message, &keywords)
+}
+
+fn keyword_references(message: &str, keywords: &[&str]) -> Vec<String> {
+    let mut references = Vec::new();
+    // next word may be a reference
+    let mut expect = false;
+    // previous word was a reference, an "and" continues the list
+    let mut listing = false;
+
+    for word in message.split_whitespace() {
+        let keyword = word.trim_end_matches(':').to_lowercase();
+        if keywords.contains(&keyword.as_str()) {
+            expect = true;
+            listing = false;
+            continue;
+        }
+        if listing && (word.eq_ignore_ascii_case("and") || word == "&" || word == ",") {
+            expect = true;
+            listing = false;
+            continue;
+        }
+        if !expect {
+            listing = false;
+            continue;
+        }
+
+        let reference = word
+            .trim_start_matches('#')
+            .trim_end_matches(&[',', '.', ';', ':', ')'][..]);
+        let hex = !reference.is_empty() && reference.chars().all(|c| c.is_ascii_hexdigit());
+        if hex && reference.len() == HASH_LEN {
+            references.push(reference.to_string());
+            expect = word.ends_with(',');
+            listing = true;
+        } else {
+            expect = false;
+            listing = false;
+        }
+    }
+
+    references
+}
+
+/// Checks the issue references of a commit message. Status returns the status of a referenced
+/// issue or None for unknown issues, issues closed by the checked commit itself are reported
+/// as open since they were open when it was written.
+pub fn reference_problems(
+    message: &str,
+    status: &dyn Fn(&str) -> Result<Option<IssueStatus>, String>,
+) -> Vec<String> {
+    let mut problems = Vec::new();
+    for reference in references(message) {
+        match status(&reference) {
+            Ok(Some(v)) if v == IssueStatus::Closed || v == IssueStatus::Rejected => {
+                problems.push(format!("Issue {} is {}", reference, v));
+            }
+            Ok(Some(_)) => {}
+            Ok(None) => problems.push(format!("Unknown issue {}", reference)),
+            Err(e) => problems.push(format!("Invalid issue hash {}: {}", reference, e)),
+        }
+    }
+    problems
+}
+
+/// Returns the hooks directory of the git repository containing working_dir
+pub fn hooks_dir(working_dir: &PathBuf) -> Result<PathBuf, String> {
+    let output = process::Command::new("git")
+        .arg("rev-parse")
+        .arg("--git-path")
+        .arg("hooks")
+        .current_dir(working_dir)
+        .output()
+        .map_err(|e| format!("failed to run git: {}", e))?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+
+    let path = PathBuf::from(String::from_utf8_lossy(&output.stdout).trim());
+    Ok(working_dir.join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn closing_references_lists() {
+        let message = "Fixes deadbeefdeadbeef, deadbeefdeadbee0 and #deadbeefdeadbee1.";
+        assert_eq!(
+            closing_references(message),
+            vec!["deadbeefdeadbeef", "deadbeefdeadbee0", "deadbeefdeadbee1"]
+        );
+    }
+
+    #[test]
+    fn closing_references_need_keyword() {
+        assert!(closing_references("Add deadbeefdeadbeef").is_empty());
+        assert!(closing_references("Refs deadbeefdeadbeef").is_empty());
+        assert_eq!(
+            references("Refs: deadbeefdeadbeef"),
+            vec!["deadbeefdeadbeef"]
+        );
+    }
+
+    #[test]
+    fn prose_is_no_reference() {
+        assert!(references("Fix dead code").is_empty());
+        assert!(references("see face detection").is_empty());
+        assert!(references("Fixes 2024 regression").is_empty());
+        assert!(references("issues deadbeef and cafe").is_empty());
+    }
+
+    #[test]
+    fn marked_references() {
+        assert_eq!(
+            references("see #deadbeefdeadbeef)"),
+            vec!["deadbeefdeadbeef"]
+        );
+        assert!(references("see #dead").is_empty());
+        assert!(references("Closes #12").is_empty());
+        assert!(references("Closes #").is_empty());
+    }
+
+    #[test]
+    fn reference_problems_of_hook_messages() {
+        let status = |reference: &str| match reference {
+            "deadbeefdeadbeef" => Ok(Some(IssueStatus::Open)),
+            "deadbeefdeadbee0" => Ok(Some(IssueStatus::Closed)),
+            "deadbeefdeadbee1" => Ok(None),
+            v => Err(format!("unexpected lookup of {}", v)),
+        };
+        let message = "Fix parser\n\nCloses #12, fixes #dead\nFixes deadbeefdeadbeef";
+        assert!(reference_problems(message, &status).is_empty());
+
+        let message = "Refs deadbeefdeadbee0 and #deadbeefdeadbee1";
+        assert_eq!(
+            reference_problems(message, &status),
+            vec![
+                "Issue deadbeefdeadbee0 is Closed",
+                "Unknown issue deadbeefdeadbee1"
+            ]
+        );
+    }
+}
@@ -0,0 +1,63 @@
+use chrono::NaiveDate;
+
+use super::{Config, CustomField, CustomFieldKind};
+
+/// Parses a 'key=value' custom field argument against the fields declared in the tracker config.
+/// Returns the field name with its normalised value, an empty value unsets the field.
+pub fn parse(config: &Config, arg: &str) -> Result<(String, Option<String>), String> {
+    let mut parts = arg.splitn(2, '=');
+    let key = parts.next().unwrap_or_default().trim();
+    let value = match parts.next() {
+        Some(v) => v.trim(),
+        None => return Err(format!("expected key=value, got '{}'", arg)),
+    };
+
+    let field = match field(config, key) {
+        Some(v) => v,
+        None => {
+            let names: Vec<&str> = config
+                .custom_fields()
+                .iter()
+                .map(|v| v.name().as_str())
+                .collect();
+            return Err(format!(
+                "unknown field '{}', declared fields: {}",
+                key,
+                if names.is_empty() {
+                    "none".to_string()
+                } else {
+                    names.join(", ")
+                }
+            ));
+        }
+    };
+    if value.is_empty() {
+        return Ok((field.name().to_string(), None));
+    }
+
+    let value = match *field.kind() {
+        CustomFieldKind::String | CustomFieldKind::User => value.to_string(),
+        CustomFieldKind::Int => match value.parse::<i64>() {
+            Ok(v) => v.to_string(),
+            Err(e) => return Err(format!("'{}' is not an integer, {}", value, e)),
+        },
+        CustomFieldKind::Enum(ref variants) => {
+            match variants.iter().find(|v| v.eq_ignore_ascii_case(value)) {
+                Some(v) => v.to_string(),
+                None => return Err(format!("'{}' is not one of {}", value, variants.join(", "))),
+            }
+        }
+        CustomFieldKind::Date => match NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+            Ok(v) => v.format("%Y-%m-%d").to_string(),
+            Err(e) => return Err(format!("'{}' is not a date (YYYY-MM-DD), {}", value, e)),
+        },
+    };
+    Ok((field.name().to_string(), Some(value)))
+}
+
+fn field<'a>(config: &'a Config, key: &str) -> Option<&'a CustomField> {
+    config
+        .custom_fields()
+        .iter()
+        .find(|v| v.name().eq_ignore_ascii_case(key))
+}
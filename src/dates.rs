@@ -0,0 +1,269 @@
+use std::convert::TryFrom;
+
+use chrono::{
+    DateTime, Datelike, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone, Weekday,
+};
+
+/// Largest offset in seconds, chrono durations panic beyond i64::MAX milliseconds
+const MAX_SECONDS: i64 = i64::MAX / 1000;
+
+/// Parses absolute, relative and natural date inputs into the inclusive range of timestamps
+/// they denote, e.g. a day covers 00:00:00 to 23:59:59 and a minute 60 seconds.
+///
+/// Accepted inputs:
+///   2026-10-17, 2026-10-17 14:30, 2026-10-17T14:30, 2026-10-17T14:30:00+02:00
+///   now, today, tomorrow, yesterday
+///   +3d, -2w, +1m, +1y (days, weeks, months, years), +4h (hours)
+///   friday, next friday, last friday
+///   start of week|month|year, end of week|month|year
+pub fn parse(value: &str, now: DateTime<Local>) -> Result<(i64, i64), String> {
+    let input = value.trim().to_lowercase();
+    let words: Vec<&str> = input.split_whitespace().collect();
+    let today = now.date().naive_local();
+
+    if let Some(v) = absolute(value.trim()) {
+        return Ok(v);
+    }
+
+    let day = match words.as_slice() {
+        ["now"] => return Ok((now.timestamp(), now.timestamp())),
+        ["today"] => Some(today),
+        ["tomorrow"] => add_days(today, 1),
+        ["yesterday"] => add_days(today, -1),
+        [v] if v.len() > 2 && v.is_ascii() && v.starts_with(&['+', '-'][..]) => {
+            let sign = if v.starts_with('-') { -1 } else { 1 };
+            let (amount, unit) = v[1..].split_at(v.len() - 2);
+            let amount = match amount.parse::<i64>() {
+                Ok(v) => v * sign,
+                Err(_) => return Err(error(value)),
+            };
+            match unit {
+                "h" => {
+                    let ts = amount
+                        .checked_mul(60 * 60)
+                        .filter(|v| (-MAX_SECONDS..=MAX_SECONDS).contains(v))
+                        .and_then(|v| now.checked_add_signed(Duration::seconds(v)))
+                        .ok_or_else(|| error(value))?
+                        .timestamp();
+                    return Ok((ts, ts));
+                }
+                "d" => add_days(today, amount),
+                "w" => amount.checked_mul(7).and_then(|v| add_days(today, v)),
+                "m" => add_months(today, amount),
+                "y" => amount.checked_mul(12).and_then(|v| add_months(today, v)),
+                _ => return Err(error(value)),
+            }
+        }
+        [v] => match weekday(v) {
+            // the upcoming weekday, today included
+            Some(w) => add_days(today, days_until(today.weekday(), w) % 7),
+            None => return Err(error(value)),
+        },
+        ["next", v] => match weekday(v) {
+            Some(w) => add_days(today, days_until(today.weekday(), w)),
+            None if *v == "week" => add_days(today, 7),
+            None if *v == "month" => add_months(today, 1),
+            None if *v == "year" => add_months(today, 12),
+            None => return Err(error(value)),
+        },
+        ["last", v] => match weekday(v) {
+            Some(w) => add_days(today, -days_until(w, today.weekday())),
+            None => return Err(error(value)),
+        },
+        [edge, "of", period] => {
+            let start = match *period {
+                "week" => today - Duration::days(today.weekday().num_days_from_monday() as i64),
+                "month" => today.with_day(1).unwrap(),
+                "year" => today.with_ordinal(1).unwrap(),
+                _ => return Err(error(value)),
+            };
+            match *edge {
+                "start" | "beginning" => Some(start),
+                "end" => match *period {
+                    "week" => add_days(start, 6),
+                    "month" => add_months(start, 1).and_then(|v| add_days(v, -1)),
+                    _ => add_months(start, 12).and_then(|v| add_days(v, -1)),
+                },
+                _ => return Err(error(value)),
+            }
+        }
+        _ => return Err(error(value)),
+    };
+
+    day.and_then(day_range).ok_or_else(|| error(value))
+}
+
+fn absolute(value: &str) -> Option<(i64, i64)> {
+    if let Ok(v) = NaiveDate::parse_from_str(value, "%Y-%m-%d") {
+        return day_range(v);
+    }
+    for format in &["%Y-%m-%d %H:%M", "%Y-%m-%dT%H:%M"] {
+        if let Ok(v) = NaiveDateTime::parse_from_str(value, format) {
+            let ts = Local.from_local_datetime(&v).earliest()?.timestamp();
+            return Some((ts, ts + 59));
+        }
+    }
+    if let Ok(v) = DateTime::parse_from_rfc3339(value) {
+        return Some((v.timestamp(), v.timestamp()));
+    }
+    for format in &["%Y-%m-%dT%H:%M%z", "%Y-%m-%d %H:%M%z"] {
+        if let Ok(v) = DateTime::<FixedOffset>::parse_from_str(value, format) {
+            return Some((v.timestamp(), v.timestamp() + 59));
+        }
+    }
+    None
+}
+
+fn day_range(day: NaiveDate) -> Option<(i64, i64)> {
+    let first = Local
+        .from_local_datetime(&day.and_hms(0, 0, 0))
+        .earliest()?;
+    let last = Local
+        .from_local_datetime(&day.and_hms(23, 59, 59))
+        .latest()?;
+    Some((first.timestamp(), last.timestamp()))
+}
+
+/// Adds days, None when the result is out of the supported range
+fn add_days(day: NaiveDate, days: i64) -> Option<NaiveDate> {
+    let seconds = days.checked_mul(24 * 60 * 60)?;
+    if !(-MAX_SECONDS..=MAX_SECONDS).contains(&seconds) {
+        return None;
+    }
+    day.checked_add_signed(Duration::seconds(seconds))
+}
+
+/// Adds months, clamping the day to the length of the resulting month. None when the result
+/// is out of the supported range.
+fn add_months(day: NaiveDate, months: i64) -> Option<NaiveDate> {
+    let month0 = (day.year() as i64 * 12 + day.month0() as i64).checked_add(months)?;
+    let year = i32::try_from(month0.div_euclid(12)).ok()?;
+    let month = month0.rem_euclid(12) as u32 + 1;
+    (1..=day.day())
+        .rev()
+        .find_map(|d| NaiveDate::from_ymd_opt(year, month, d))
+}
+
+fn weekday(v: &str) -> Option<Weekday> {
+    match v {
+        "monday" | "mon" => Some(Weekday::Mon),
+        "tuesday" | "tue" => Some(Weekday::Tue),
+        "wednesday" | "wed" => Some(Weekday::Wed),
+        "thursday" | "thu" => Some(Weekday::Thu),
+        "friday" | "fri" => Some(Weekday::Fri),
+        "saturday" | "sat" => Some(Weekday::Sat),
+        "sunday" | "sun" => Some(Weekday::Sun),
+        _ => None,
+    }
+}
+
+/// Days from one weekday to the next occurrence of another, a full week for the same day
+fn days_until(from: Weekday, to: Weekday) -> i64 {
+    let days =
+        (to.num_days_from_monday() as i64 - from.num_days_from_monday() as i64).rem_euclid(7);
+    if days == 0 {
+        7
+    } else {
+        days
+    }
+}
+
+fn error(value: &str) -> String {
+    format!(
+        "invalid date: {}, expected e.g. 2026-10-17, 2026-10-17 14:30, today, +3d, next friday or end of month",
+        value
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Saturday
+    fn now() -> DateTime<Local> {
+        Local.ymd(2026, 10, 17).and_hms(14, 30, 0)
+    }
+
+    fn day(y: i32, m: u32, d: u32) -> Result<(i64, i64), String> {
+        Ok(day_range(NaiveDate::from_ymd(y, m, d)).unwrap())
+    }
+
+    #[test]
+    fn parse_absolute() {
+        assert_eq!(parse("2026-01-02", now()), day(2026, 1, 2));
+        let (first, last) = parse("2026-01-02 10:15", now()).unwrap();
+        assert_eq!(last - first, 59);
+        assert_eq!(
+            parse("2026-01-02T10:15:00+02:00", now()),
+            Ok((1767341700, 1767341700))
+        );
+    }
+
+    #[test]
+    fn parse_natural() {
+        assert_eq!(
+            parse("now", now()),
+            Ok((now().timestamp(), now().timestamp()))
+        );
+        assert_eq!(parse("Today", now()), day(2026, 10, 17));
+        assert_eq!(parse("tomorrow", now()), day(2026, 10, 18));
+        assert_eq!(parse("yesterday", now()), day(2026, 10, 16));
+        assert_eq!(parse("saturday", now()), day(2026, 10, 17));
+        assert_eq!(parse("fri", now()), day(2026, 10, 23));
+        assert_eq!(parse("next saturday", now()), day(2026, 10, 24));
+        assert_eq!(parse("last saturday", now()), day(2026, 10, 10));
+        assert_eq!(parse("next month", now()), day(2026, 11, 17));
+        assert_eq!(parse("start of week", now()), day(2026, 10, 12));
+        assert_eq!(parse("end of week", now()), day(2026, 10, 18));
+        assert_eq!(parse("end of month", now()), day(2026, 10, 31));
+        assert_eq!(parse("start of year", now()), day(2026, 1, 1));
+        assert_eq!(parse("end of year", now()), day(2026, 12, 31));
+    }
+
+    #[test]
+    fn parse_relative() {
+        assert_eq!(parse("+3d", now()), day(2026, 10, 20));
+        assert_eq!(parse("-2w", now()), day(2026, 10, 3));
+        assert_eq!(parse("+1m", now()), day(2026, 11, 17));
+        assert_eq!(parse("-10m", now()), day(2025, 12, 17));
+        assert_eq!(parse("+1y", now()), day(2027, 10, 17));
+        let ts = now().timestamp() + 4 * 60 * 60;
+        assert_eq!(parse("+4h", now()), Ok((ts, ts)));
+    }
+
+    #[test]
+    fn add_months_clamps_day() {
+        let day = NaiveDate::from_ymd(2026, 1, 31);
+        assert_eq!(add_months(day, 1), Some(NaiveDate::from_ymd(2026, 2, 28)));
+        assert_eq!(add_months(day, -2), Some(NaiveDate::from_ymd(2025, 11, 30)));
+    }
+
+    #[test]
+    fn parse_out_of_range() {
+        for v in &[
+            "+99999999d",
+            "-99999999999999w",
+            "+9223372036854775807d",
+            "+9223372036854775807h",
+            "+99999999999m",
+            "+999999999999999999y",
+        ] {
+            assert!(parse(v, now()).is_err(), "{}", v);
+        }
+    }
+
+    #[test]
+    fn parse_invalid() {
+        for v in &[
+            "",
+            "+d",
+            "+3x",
+            "soon",
+            "next day",
+            "middle of week",
+            "2026-13-01",
+        ] {
+            assert!(parse(v, now()).is_err(), "{}", v);
+        }
+    }
+}
@@ -1,13 +1,25 @@
 extern crate yansi;
 
+use std::collections::{BTreeMap, BTreeSet};
+
 use cobweb_core::Config;
-use cobweb_core::{Issue, IssuePriority};
+use cobweb_core::Issue;
 
 use chrono::{Local, TimeZone};
+use terminal_size::{terminal_size, Width};
+
+use super::attachments;
+use super::board::Column;
+use super::fields::Field;
+use super::kinds::Kinds;
+use super::milestones::Summary;
+use super::settings::{Settings, KEYS};
+use super::stats::{self, Stats};
+use super::worklog::{self, TimeTotals};
 
 use self::yansi::{Color, Style};
 
-pub fn issue_short(issue: &Issue) {
+pub fn issue_short(issue: &Issue, abbrev: &str) {
     // styles
     let sty_begin = Style::new(Color::Yellow);
     let sty_property = Style::new(Color::White).bold();
@@ -16,19 +28,64 @@ pub fn issue_short(issue: &Issue) {
         "{} {} {} {} {}",
         sty_begin.paint(">"),
         sty_property.paint("H:"),
-        issue.hash(),
+        abbrev,
         sty_property.paint("T:"),
         issue.title()
     );
 }
 
-pub fn issue_long(issue: &Issue) {
+pub fn search_hit(issue: &Issue, abbrev: &str, score: f64, snippet: &[(String, bool)]) {
+    // styles
+    let sty_begin = Style::new(Color::Yellow);
+    let sty_property = Style::new(Color::White).bold();
+    let sty_hit = Style::new(Color::Red).bold();
+
+    println!(
+        "{} {} {} {} {} {}",
+        sty_begin.paint(">"),
+        sty_property.paint("H:"),
+        abbrev,
+        sty_property.paint("T:"),
+        issue.title(),
+        Style::new(Color::Default)
+            .dimmed()
+            .paint(format!("({:.2})", score))
+    );
+    if !snippet.is_empty() {
+        let line: String = snippet
+            .iter()
+            .map(|(v, hit)| {
+                if *hit {
+                    sty_hit.paint(v).to_string()
+                } else {
+                    v.to_string()
+                }
+            })
+            .collect();
+        println!("  {}", line);
+    }
+}
+
+pub fn issue_long(issue: &Issue, relations: &[(&str, &Issue)], kinds: &Kinds, date_format: &str) {
+    for line in issue_long_lines(issue, relations, kinds, date_format) {
+        println!("{}", line);
+    }
+}
+
+/// Lines of the detailed issue view, see issue_long
+pub fn issue_long_lines(
+    issue: &Issue,
+    relations: &[(&str, &Issue)],
+    kinds: &Kinds,
+    date_format: &str,
+) -> Vec<String> {
+    let mut lines = Vec::new();
+
     // styles
     let sty_begin = Style::new(Color::Yellow);
     let sty_property = Style::new(Color::Default).bold();
-    let sty_low = Style::new(Color::Green);
-    let sty_medium = Style::new(Color::Yellow);
-    let sty_high = Style::new(Color::Red);
+    let sty_label = Style::new(Color::Magenta);
+    let sty_comment = Style::new(Color::Cyan);
 
     /* Note: Properties in bold
        >
@@ -38,96 +95,721 @@ pub fn issue_long(issue: &Issue) {
        Author: Pegasus                  Reviser: Zeus
        Start date: 2018-04-23 10:23     Due date: 2018-04-23 10:28
        Status: in progress    Priority: moderate         Progress: 045%
+       Estimate: 4h                 Logged: 1h30m
+       Milestone: 1.0
+       Labels: backend, regression
+       component: parser
        Description:
        Lore ipsum
+       Commits:
+       0123456789abcdef0123456789abcdef01234567
+       Attachments:
+       server.log (12.3 KiB)
+       Links:
+       blocked by cafebabecafebabe (open) Title of the blocking issue
+       Comments:
+       Zeus, 2018-04-24 08:12:
+       Dolor sit amet
     */
 
     // seperator
-    println!("{}", sty_begin.paint(">"));
+    lines.push(sty_begin.paint(">").to_string());
     // title
-    println!("{} {}", sty_property.paint("Title:"), issue.title());
+    lines.push(format!(
+        "{} {}",
+        sty_property.paint("Title:"),
+        issue.title()
+    ));
     // type | creation date
     let dt = Local.timestamp(issue.creation_date(), 0);
-    let dt = dt.format("%Y-%m-%d %H:%M").to_string();
-    let typ = issue.typ().to_string();
-    println!(
-        "{} {:<26} {} {}",
+    let dt = dt.format(date_format).to_string();
+    let typ = format!("{:<26}", issue.typ().to_string());
+    lines.push(format!(
+        "{} {} {} {}",
         sty_property.paint("Type:"),
-        typ,
+        kinds.type_style(&issue.typ().to_string()).paint(typ),
         sty_property.paint("Creation date:"),
         dt
-    );
+    ));
     // hash | parent
     let hash = issue.hash().to_string();
     let parent = match *issue.parent() {
         Some(ref v) => v.to_string(),
         None => "-".to_string(),
     };
-    println!(
+    lines.push(format!(
         "{} {:<26} {} {}",
         sty_property.paint("Hash:"),
         hash,
         sty_property.paint("Parent:"),
         parent
-    );
+    ));
     // author | reviser
     let reviser = issue.assigned_to().to_owned().unwrap_or(String::from("-"));
-    println!(
+    lines.push(format!(
         "{} {:<24} {} {}",
         sty_property.paint("Author:"),
         issue.author(),
         sty_property.paint("Reviser:"),
         &reviser
-    );
+    ));
     // start date | due date
     let start_dt = Local.timestamp(issue.start_date(), 0);
-    let start_dt = start_dt.format("%Y-%m-%d %H:%M").to_string();
+    let start_dt = start_dt.format(date_format).to_string();
     let due_dt = match *issue.due_date() {
         Some(v) => {
             let dt = Local.timestamp(v, 0);
-            dt.format("%Y-%m-%d %H:%M").to_string()
+            dt.format(date_format).to_string()
         }
         None => "-".to_string(),
     };
-    println!(
+    lines.push(format!(
         "{} {:<20} {} {}",
         sty_property.paint("Start date:"),
         start_dt,
         sty_property.paint("Due date:"),
         due_dt
-    );
+    ));
     // status | priority | progress
     let status = issue.status().to_string();
-    let sty_priority = match *issue.priority() {
-        IssuePriority::Low => sty_low,
-        IssuePriority::Medium => sty_medium,
-        IssuePriority::High => sty_high,
-    };
+    let sty_priority = kinds.priority_style(&issue.priority().to_string());
     let priority = issue.priority().to_string();
-    println!(
-        "{} {:<14} {} {:<16} {} {:>3}%",
+    let rollup = if issue.progress_rollup() {
+        " (rolled up)"
+    } else {
+        ""
+    };
+    lines.push(format!(
+        "{} {:<14} {} {:<16} {} {:>3}%{}",
         sty_property.paint("Status:"),
         status,
         sty_property.paint("Priority:"),
         sty_priority.paint(priority),
         sty_property.paint("Progress:"),
-        issue.progress()
-    );
+        issue.progress(),
+        rollup
+    ));
+    // estimate | logged
+    let logged: u64 = issue.work_logs().iter().map(|log| log.duration()).sum();
+    lines.push(format!(
+        "{} {:<20} {} {}",
+        sty_property.paint("Estimate:"),
+        issue
+            .estimate()
+            .map(worklog::format)
+            .unwrap_or_else(|| "-".to_string()),
+        sty_property.paint("Logged:"),
+        worklog::format(logged)
+    ));
+    // milestone
+    let milestone = issue.milestone().to_owned().unwrap_or(String::from("-"));
+    lines.push(format!(
+        "{} {}",
+        sty_property.paint("Milestone:"),
+        milestone
+    ));
+    // labels
+    let labels = if issue.labels().is_empty() {
+        "-".to_string()
+    } else {
+        let labels: Vec<String> = issue
+            .labels()
+            .iter()
+            .map(|v| sty_label.paint(v).to_string())
+            .collect();
+        labels.join(", ")
+    };
+    lines.push(format!("{} {}", sty_property.paint("Labels:"), labels));
+    // custom fields
+    for (key, value) in issue.custom_fields() {
+        lines.push(format!(
+            "{} {}",
+            sty_property.paint(format!("{}:", key)),
+            value
+        ));
+    }
     // description
-    println!("{}", sty_property.paint("Description:"));
+    lines.push(sty_property.paint("Description:").to_string());
     if let Some(ref v) = *issue.description() {
-        println!("{}", v);
+        lines.extend(v.lines().map(|v| v.to_string()));
     } else {
-        println!("-");
+        lines.push("-".to_string());
     }
+    // commits
+    if !issue.commits().is_empty() {
+        lines.push(sty_property.paint("Commits:").to_string());
+        for commit in issue.commits() {
+            lines.push(commit.to_string());
+        }
+    }
+    // attachments
+    if !issue.attachments().is_empty() {
+        lines.push(sty_property.paint("Attachments:").to_string());
+        for attachment in issue.attachments() {
+            lines.push(format!(
+                "{} ({})",
+                attachment.name(),
+                attachments::format_size(attachment.size())
+            ));
+        }
+    }
+    // links
+    if !relations.is_empty() {
+        lines.push(sty_property.paint("Links:").to_string());
+        for (relation, other) in relations {
+            lines.push(format!(
+                "{} {} ({}) {}",
+                relation,
+                other.hash(),
+                other.status(),
+                other.title()
+            ));
+        }
+    }
+    // comments
+    if !issue.comments().is_empty() {
+        lines.push(sty_property.paint("Comments:").to_string());
+        for comment in issue.comments() {
+            let dt = Local.timestamp(comment.creation_date(), 0);
+            let dt = dt.format(date_format).to_string();
+            lines.push(format!("{}, {}:", sty_comment.paint(comment.author()), dt));
+            lines.extend(comment.text().lines().map(|v| v.to_string()));
+        }
+    }
+    lines.push(String::new());
+    lines
+}
+
+pub fn issue_table(issues: &[&Issue], columns: &[Field], kinds: &Kinds, date_format: &str) {
+    // styles
+    let sty_property = Style::new(Color::Default).bold();
+
+    let rows: Vec<Vec<String>> = issues
+        .iter()
+        .map(|issue| {
+            columns
+                .iter()
+                .map(|column| column.value(issue, date_format))
+                .collect()
+        })
+        .collect();
+
+    // natural column widths
+    let mut widths: Vec<usize> = columns
+        .iter()
+        .map(|column| column.to_string().chars().count())
+        .collect();
+    for row in &rows {
+        for (i, v) in row.iter().enumerate() {
+            widths[i] = widths[i].max(v.chars().count());
+        }
+    }
+
+    // shrink widest columns until the table fits into the terminal
+    if let Some((Width(width), _)) = terminal_size() {
+        let width = width as usize;
+        let separators = columns.len().saturating_sub(1) * 2;
+        while widths.iter().sum::<usize>() + separators > width {
+            let (i, widest) = match widths.iter().enumerate().max_by_key(|(_, v)| **v) {
+                Some((i, v)) => (i, *v),
+                None => break,
+            };
+            if widest <= 3 {
+                break;
+            }
+            widths[i] -= 1;
+        }
+    }
+
+    let header: Vec<String> = columns
+        .iter()
+        .zip(&widths)
+        .map(|(column, width)| {
+            sty_property
+                .paint(cell(&column.to_string(), *width))
+                .to_string()
+        })
+        .collect();
+    println!("{}", header.join("  ").trim_end());
+
+    for (issue, row) in issues.iter().zip(&rows) {
+        let row: Vec<String> = row
+            .iter()
+            .zip(columns.iter().zip(&widths))
+            .map(|(v, (column, width))| match *column {
+                Field::Type => kinds
+                    .type_style(&issue.typ().to_string())
+                    .paint(cell(v, *width))
+                    .to_string(),
+                Field::Priority => kinds
+                    .priority_style(&issue.priority().to_string())
+                    .paint(cell(v, *width))
+                    .to_string(),
+                _ => cell(v, *width),
+            })
+            .collect();
+        println!("{}", row.join("  ").trim_end());
+    }
+}
+
+pub fn issue_tree(
+    roots: &[&Issue],
+    children: &BTreeMap<String, Vec<&Issue>>,
+    visible: &BTreeSet<String>,
+    matched: &BTreeSet<String>,
+    depth: Option<usize>,
+    kinds: &Kinds,
+) {
+    for root in roots {
+        issue_tree_node(root, "", "", children, visible, matched, depth, kinds);
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn issue_tree_node(
+    issue: &Issue,
+    prefix: &str,
+    connector: &str,
+    children: &BTreeMap<String, Vec<&Issue>>,
+    visible: &BTreeSet<String>,
+    matched: &BTreeSet<String>,
+    depth: Option<usize>,
+    kinds: &Kinds,
+) {
+    // styles
+    let sty_tree = Style::new(Color::Yellow);
+    let sty_unmatched = Style::new(Color::Default).dimmed();
+
+    /* Note: Issues only shown as ancestors of matches are dimmed
+       deadbeefdeadbeef Open High 045% Title of the issue
+       ├── deadbeefdeadbee0 InProgress Medium 060% Child issue
+       │   └── deadbeefdeadbee1 Closed Low 100% Grandchild issue
+       └── deadbeefdeadbee2 Review Low 090% Child issue
+    */
+
+    let hash = issue.hash().to_string();
+    let (sty_node, sty_priority) = if matched.contains(&hash) {
+        (
+            Style::new(Color::Default),
+            kinds.priority_style(&issue.priority().to_string()),
+        )
+    } else {
+        (sty_unmatched, sty_unmatched)
+    };
+    println!(
+        "{}{} {} {} {} {}",
+        sty_tree.paint(format!("{}{}", prefix, connector)),
+        sty_node.paint(&hash),
+        sty_node.paint(issue.status()),
+        sty_priority.paint(issue.priority()),
+        sty_node.paint(format!("{:>3}%", issue.progress())),
+        sty_node.paint(issue.title())
+    );
+
+    if depth == Some(0) {
+        return;
+    }
+    let nodes: Vec<&&Issue> = match children.get(&hash) {
+        Some(v) => v
+            .iter()
+            .filter(|child| visible.contains(&child.hash().to_string()))
+            .collect(),
+        None => return,
+    };
+
+    // children are indented below the connector of their parent
+    let prefix = match connector {
+        "├── " => format!("{}│   ", prefix),
+        "└── " => format!("{}    ", prefix),
+        _ => prefix.to_string(),
+    };
+    for (i, child) in nodes.iter().enumerate() {
+        let connector = if i + 1 == nodes.len() {
+            "└── "
+        } else {
+            "├── "
+        };
+        let depth = depth.map(|v| v - 1);
+        issue_tree_node(
+            child, &prefix, connector, children, visible, matched, depth, kinds,
+        );
+    }
+}
+
+pub fn time_report(rows: &[(&Issue, TimeTotals)], assignees: &BTreeMap<String, TimeTotals>) {
+    // styles
+    let sty_property = Style::new(Color::Default).bold();
+
+    /* Note: Totals include child issues
+       Hash              Logged  Estimate  Total logged  Total estimate  Title
+       deadbeefdeadbeef  1h30m   4h        6h            12h             Title of the issue
+    */
+
+    println!(
+        "{}",
+        sty_property.paint(format!(
+            "{:<16}  {:>8}  {:>8}  {:>12}  {:>14}  {}",
+            "Hash", "Logged", "Estimate", "Total logged", "Total estimate", "Title"
+        ))
+    );
+    for (issue, totals) in rows {
+        println!(
+            "{:<16}  {:>8}  {:>8}  {:>12}  {:>14}  {}",
+            issue.hash().to_string(),
+            worklog::format(totals.logged),
+            worklog::format(totals.estimate),
+            worklog::format(totals.total_logged),
+            worklog::format(totals.total_estimate),
+            issue.title()
+        );
+    }
+
+    println!();
+    println!(
+        "{}",
+        sty_property.paint(format!(
+            "{:<16}  {:>8}  {:>8}",
+            "Assignee", "Logged", "Estimate"
+        ))
+    );
+    let mut logged = 0;
+    let mut estimate = 0;
+    for (assignee, totals) in assignees {
+        println!(
+            "{:<16}  {:>8}  {:>8}",
+            assignee,
+            worklog::format(totals.logged),
+            worklog::format(totals.estimate)
+        );
+        logged += totals.logged;
+        estimate += totals.estimate;
+    }
+    println!(
+        "{}",
+        sty_property.paint(format!(
+            "{:<16}  {:>8}  {:>8}",
+            "Total",
+            worklog::format(logged),
+            worklog::format(estimate)
+        ))
+    );
+}
+
+pub fn milestones(summaries: &[Summary], date_format: &str) {
+    // styles
+    let sty_begin = Style::new(Color::Yellow);
+    let sty_property = Style::new(Color::Default).bold();
+    let sty_overdue = Style::new(Color::Red).bold();
+
+    /* Note: Properties in bold
+       > 1.0 (overdue)
+       Target date: 2018-04-23 10:23     Progress: 045%
+       Issues: 3 open, 5 closed
+       Description of the milestone
+    */
+
+    if summaries.is_empty() {
+        println!("No milestones");
+        return;
+    }
+    for summary in summaries {
+        let milestone = summary.milestone;
+        if summary.overdue {
+            println!(
+                "{} {} {}",
+                sty_begin.paint(">"),
+                sty_property.paint(milestone.name()),
+                sty_overdue.paint("(overdue)")
+            );
+        } else {
+            println!(
+                "{} {}",
+                sty_begin.paint(">"),
+                sty_property.paint(milestone.name())
+            );
+        }
+        let target_date = match *milestone.target_date() {
+            Some(v) => Local.timestamp(v, 0).format(date_format).to_string(),
+            None => "-".to_string(),
+        };
+        println!(
+            "{} {:<20} {} {:>3}%",
+            sty_property.paint("Target date:"),
+            target_date,
+            sty_property.paint("Progress:"),
+            summary.progress
+        );
+        println!(
+            "{} {} open, {} closed",
+            sty_property.paint("Issues:"),
+            summary.open,
+            summary.closed
+        );
+        if let Some(ref v) = *milestone.description() {
+            println!("{}", v);
+        }
+        println!();
+    }
+}
+
+/// Prints the breakdowns, flow metrics and throughput of the stats subcommand
+pub fn stats(stats: &Stats) {
+    // styles
+    let sty_property = Style::new(Color::Default).bold();
+    let sty_bar = Style::new(Color::Cyan);
+
+    /* Note: Headers in bold, bars relative to the largest count
+       Issues: 42
+       By status
+       Open          20  48%
+       ...
+       Lead time:  12 closed  mean 3d 4h  median 2d 1h  85th percentile 6d 2h
+       2 closed issues without close date in their history are left out
+       Throughput
+       2026-W41       4  ████
+    */
+
+    println!("{} {}", sty_property.paint("Issues:"), stats.total);
+    for (name, counts) in &stats.groups {
+        println!();
+        println!("{}", sty_property.paint(format!("By {}", name)));
+        for (key, count) in counts {
+            println!(
+                "{:<20} {:>6} {:>4}%",
+                cell(key, 20),
+                count,
+                count * 100 / stats.total.max(1)
+            );
+        }
+    }
+
+    println!();
+    let durations = [
+        ("Lead time:", &stats.lead_time),
+        ("Cycle time:", &stats.cycle_time),
+    ];
+    for (name, durations) in durations.iter() {
+        match durations {
+            Some(v) => println!(
+                "{} {} closed  mean {}  median {}  85th percentile {}",
+                sty_property.paint(format!("{:<11}", name)),
+                v.count,
+                stats::format_duration(v.mean),
+                stats::format_duration(v.median),
+                stats::format_duration(v.p85)
+            ),
+            None => println!("{} -", sty_property.paint(format!("{:<11}", name))),
+        }
+    }
+    if stats.undated > 0 {
+        println!(
+            "{} closed issues without close date in their history are left out",
+            stats.undated
+        );
+    }
+
     println!();
+    println!("{}", sty_property.paint("Throughput"));
+    let max = stats.throughput.iter().map(|(_, v)| *v).max().unwrap_or(0);
+    for (week, count) in &stats.throughput {
+        // bars are at most 40 characters wide
+        let bar = "█".repeat((count * 40).div_ceil(max.max(1)));
+        println!("{:<20} {:>6}  {}", week, count, sty_bar.paint(bar));
+    }
 }
 
-pub fn config(config: &Config) {
-    // TODO: use coloring from above
-    if let Some(user) = config.user() {
-        println!("User: {}", user);
+/// Prints the columns side by side with one card per issue
+pub fn board(columns: &[Column], abbrev_len: usize, kinds: &Kinds) {
+    // styles
+    let sty_property = Style::new(Color::Default).bold();
+    let sty_over = Style::new(Color::Red).bold();
+    let sty_border = Style::new(Color::Default).dimmed();
+
+    /* Note: Headers in bold, columns over their limit in red
+       Open 2                 │ InProgress 4/3 !       │ Review 0/2
+       ────────────────────── │ ────────────────────── │ ──────────────────────
+       deadb High             │ cafe Medium            │
+       Title of the issue     │ Title of another issue │
+       @Zeus                  │ unassigned             │
+    */
+
+    if columns.is_empty() {
+        return;
+    }
+    let width = match terminal_size() {
+        Some((Width(v), _)) => v as usize,
+        None => 120,
+    };
+    let separators = (columns.len() - 1) * 3;
+    let column_width = (width.saturating_sub(separators) / columns.len()).max(16);
+
+    // lines of each column with their style
+    let cells: Vec<Vec<(String, Style)>> = columns
+        .iter()
+        .map(|column| {
+            let sty_header = if column.over_limit() {
+                sty_over
+            } else {
+                sty_property
+            };
+            let header = match column.limit {
+                Some(limit) if column.over_limit() => {
+                    format!("{} {}/{} !", column.status, column.issues.len(), limit)
+                }
+                Some(limit) => format!("{} {}/{}", column.status, column.issues.len(), limit),
+                None => format!("{} {}", column.status, column.issues.len()),
+            };
+            let mut lines = vec![(header, sty_header), ("─".repeat(column_width), sty_header)];
+
+            for issue in &column.issues {
+                let priority = issue.priority().to_string();
+                let hash: String = issue.hash().to_string().chars().take(abbrev_len).collect();
+                lines.push((
+                    format!("{} {}", hash, priority),
+                    kinds.priority_style(&priority),
+                ));
+                for line in wrap(issue.title(), column_width, 2) {
+                    lines.push((line, Style::new(Color::Default)));
+                }
+                let assignee = match *issue.assigned_to() {
+                    Some(ref v) => format!("@{}", v),
+                    None => "unassigned".to_string(),
+                };
+                lines.push((assignee, sty_border));
+                lines.push((String::new(), Style::new(Color::Default)));
+            }
+            lines
+        })
+        .collect();
+
+    let height = cells.iter().map(|v| v.len()).max().unwrap_or(0);
+    for i in 0..height {
+        let row: Vec<String> = cells
+            .iter()
+            .map(|lines| match lines.get(i) {
+                Some((v, style)) => style.paint(cell(v, column_width)).to_string(),
+                None => cell("", column_width),
+            })
+            .collect();
+        let separator = format!(" {} ", sty_border.paint("│"));
+        println!("{}", row.join(&separator).trim_end());
+    }
+}
+
+/// Wraps text at word boundaries into at most max_lines lines of width, cutting the rest
+fn wrap(text: &str, width: usize, max_lines: usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut line = String::new();
+    for word in text.split_whitespace() {
+        let len = line.chars().count();
+        if len > 0 && len + 1 + word.chars().count() > width {
+            lines.push(line);
+            line = String::new();
+        }
+        if !line.is_empty() {
+            line.push(' ');
+        }
+        line.push_str(word);
+    }
+    if !line.is_empty() {
+        lines.push(line);
+    }
+
+    if lines.len() > max_lines {
+        lines.truncate(max_lines);
+        if let Some(last) = lines.last_mut() {
+            *last = last.chars().take(width.saturating_sub(1)).collect();
+            last.push('…');
+        }
+    }
+    lines
+}
+
+/// Pads or truncates value to exactly width characters
+fn cell(v: &str, width: usize) -> String {
+    if v.chars().count() <= width {
+        format!("{:<width$}", v, width = width)
     } else {
-        println!("User: -");
+        let mut v: String = v.chars().take(width.saturating_sub(1)).collect();
+        v.push('…');
+        v
+    }
+}
+
+pub fn history(issue: &Issue, abbrev: &str, date_format: &str) {
+    // styles
+    let sty_property = Style::new(Color::Default).bold();
+    let sty_old = Style::new(Color::Red);
+    let sty_new = Style::new(Color::Green);
+
+    issue_short(issue, abbrev);
+    if issue.history().is_empty() {
+        println!("No changes recorded");
+        return;
+    }
+    for change in issue.history() {
+        let dt = Local.timestamp(change.creation_date(), 0);
+        let dt = dt.format(date_format).to_string();
+        let old_value = change.old_value().to_owned().unwrap_or(String::from("-"));
+        let new_value = change.new_value().to_owned().unwrap_or(String::from("-"));
+        println!(
+            "{} {} {} {} -> {}",
+            dt,
+            change.author(),
+            sty_property.paint(format!("{}:", change.field())),
+            sty_old.paint(old_value),
+            sty_new.paint(new_value)
+        );
+    }
+}
+
+pub fn config(config: &Config, settings: &Settings) {
+    // styles
+    let sty_property = Style::new(Color::Default).bold();
+    let sty_layer = Style::new(Color::Default).dimmed();
+
+    for (key, _) in KEYS {
+        match settings.get(key) {
+            Some((value, layer)) => println!(
+                "{} {} {}",
+                sty_property.paint(format!("{}:", key)),
+                value,
+                sty_layer.paint(format!("({})", layer))
+            ),
+            None => println!("{} -", sty_property.paint(format!("{}:", key))),
+        }
+    }
+    let kinds = Kinds::new(config);
+    let types: Vec<String> = kinds
+        .types()
+        .iter()
+        .map(|v| format!("{}", kinds.type_style(v).paint(v)))
+        .collect();
+    println!("{} {}", sty_property.paint("Types:"), types.join(", "));
+    let priorities: Vec<String> = kinds
+        .priorities()
+        .iter()
+        .map(|v| format!("{}", kinds.priority_style(v).paint(v)))
+        .collect();
+    println!(
+        "{} {}",
+        sty_property.paint("Priorities:"),
+        priorities.join(", ")
+    );
+    if !config.transitions().is_empty() {
+        println!("{}", sty_property.paint("Workflow:"));
+        for transition in config.transitions() {
+            let mut line = format!("  {} -> {}", transition.from(), transition.to());
+            if let Some(v) = transition.subcommand() {
+                line.push_str(&format!(" via {}", v));
+            }
+            if !transition.required_fields().is_empty() {
+                line.push_str(&format!(
+                    " requires {}",
+                    transition.required_fields().join(", ")
+                ));
+            }
+            println!("{}", line);
+        }
     }
 }
@@ -0,0 +1,187 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{Local, TimeZone};
+
+use super::kinds::Kinds;
+use super::{Issue, IssueStatus};
+
+/// Issue fields usable as sort keys and table columns
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Field {
+    Hash,
+    Title,
+    Type,
+    Parent,
+    Author,
+    Created,
+    Assigned,
+    Start,
+    Due,
+    Status,
+    Priority,
+    Progress,
+    Labels,
+}
+
+impl FromStr for Field {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Field, String> {
+        match s.trim().to_lowercase().as_ref() {
+            "hash" => Ok(Field::Hash),
+            "title" => Ok(Field::Title),
+            "type" => Ok(Field::Type),
+            "parent" => Ok(Field::Parent),
+            "author" => Ok(Field::Author),
+            "created" | "creation" => Ok(Field::Created),
+            "assigned" | "assigned_to" => Ok(Field::Assigned),
+            "start" => Ok(Field::Start),
+            "due" => Ok(Field::Due),
+            "status" => Ok(Field::Status),
+            "priority" => Ok(Field::Priority),
+            "progress" => Ok(Field::Progress),
+            "labels" | "tags" => Ok(Field::Labels),
+            _ => Err(format!("unknown field '{}'", s)),
+        }
+    }
+}
+
+impl fmt::Display for Field {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Field::Hash => "Hash",
+            Field::Title => "Title",
+            Field::Type => "Type",
+            Field::Parent => "Parent",
+            Field::Author => "Author",
+            Field::Created => "Created",
+            Field::Assigned => "Assigned",
+            Field::Start => "Start",
+            Field::Due => "Due",
+            Field::Status => "Status",
+            Field::Priority => "Priority",
+            Field::Progress => "Progress",
+            Field::Labels => "Labels",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl Field {
+    /// Text representation of the field used in compact listings
+    pub fn value(self, issue: &Issue, date_format: &str) -> String {
+        let date = |ts: i64| Local.timestamp(ts, 0).format(date_format).to_string();
+        match self {
+            Field::Hash => issue.hash().to_string(),
+            Field::Title => issue.title().to_string(),
+            Field::Type => issue.typ().to_string(),
+            Field::Parent => match *issue.parent() {
+                Some(ref v) => v.to_string(),
+                None => "-".to_string(),
+            },
+            Field::Author => issue.author().to_string(),
+            Field::Created => date(issue.creation_date()),
+            Field::Assigned => issue.assigned_to().to_owned().unwrap_or(String::from("-")),
+            Field::Start => date(issue.start_date()),
+            Field::Due => match *issue.due_date() {
+                Some(v) => date(v),
+                None => "-".to_string(),
+            },
+            Field::Status => issue.status().to_string(),
+            Field::Priority => issue.priority().to_string(),
+            Field::Progress => format!("{:>3}%", issue.progress()),
+            Field::Labels => {
+                let labels: Vec<&str> = issue.labels().iter().map(|v| v.as_str()).collect();
+                if labels.is_empty() {
+                    "-".to_string()
+                } else {
+                    labels.join(",")
+                }
+            }
+        }
+    }
+
+    /// Returns whether the field holds a value, used for required workflow fields
+    pub fn is_set(self, issue: &Issue) -> bool {
+        match self {
+            Field::Title => !issue.title().trim().is_empty(),
+            Field::Parent => issue.parent().is_some(),
+            Field::Assigned => issue.assigned_to().is_some(),
+            Field::Due => issue.due_date().is_some(),
+            Field::Labels => !issue.labels().is_empty(),
+            Field::Hash
+            | Field::Type
+            | Field::Author
+            | Field::Created
+            | Field::Start
+            | Field::Status
+            | Field::Priority
+            | Field::Progress => true,
+        }
+    }
+
+    /// Compares two issues by this field, unset values are always ordered last
+    pub fn compare(self, a: &Issue, b: &Issue, descending: bool, kinds: &Kinds) -> Ordering {
+        let ord = match self {
+            Field::Parent => {
+                let a = a.parent().as_ref().map(|v| v.to_string());
+                let b = b.parent().as_ref().map(|v| v.to_string());
+                return optional(a, b, descending);
+            }
+            Field::Assigned => {
+                return optional(
+                    a.assigned_to().as_ref(),
+                    b.assigned_to().as_ref(),
+                    descending,
+                )
+            }
+            Field::Due => return optional(*a.due_date(), *b.due_date(), descending),
+            Field::Hash => a.hash().to_string().cmp(&b.hash().to_string()),
+            Field::Title => a.title().to_lowercase().cmp(&b.title().to_lowercase()),
+            Field::Type => a.typ().to_string().cmp(&b.typ().to_string()),
+            Field::Author => a.author().cmp(b.author()),
+            Field::Created => a.creation_date().cmp(&b.creation_date()),
+            Field::Start => a.start_date().cmp(&b.start_date()),
+            Field::Status => status_rank(a.status()).cmp(&status_rank(b.status())),
+            Field::Priority => kinds
+                .priority_rank(a.priority())
+                .cmp(&kinds.priority_rank(b.priority())),
+            Field::Progress => a.progress().cmp(&b.progress()),
+            Field::Labels => a.labels().len().cmp(&b.labels().len()),
+        };
+        if descending {
+            ord.reverse()
+        } else {
+            ord
+        }
+    }
+}
+
+fn optional<T: Ord>(a: Option<T>, b: Option<T>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => {
+            let ord = a.cmp(&b);
+            if descending {
+                ord.reverse()
+            } else {
+                ord
+            }
+        }
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+pub fn status_rank(status: &IssueStatus) -> u8 {
+    match *status {
+        IssueStatus::Open => 0,
+        IssueStatus::InProgress => 1,
+        IssueStatus::Review => 2,
+        IssueStatus::Halted => 3,
+        IssueStatus::Closed => 4,
+        IssueStatus::Rejected => 5,
+    }
+}
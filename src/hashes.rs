@@ -0,0 +1,87 @@
+use super::{IssueFilter, IssueHash, IssuesHandler};
+
+/// Shortest abbreviation used when printing issue hashes
+const MIN_ABBREV_LEN: usize = 4;
+
+/// Resolves a full issue hash or a unique prefix of it, the way git resolves commits
+pub fn resolve(handler: &IssuesHandler, prefix: &str) -> Result<IssueHash, String> {
+    let prefix = prefix.trim().to_lowercase();
+    if prefix.is_empty() {
+        return Err("empty issue hash".to_string());
+    }
+
+    let mut candidates = Vec::new();
+    for (_, issue) in handler.issues_filtered(&IssueFilter::new()) {
+        let hash = issue.hash().to_string().to_lowercase();
+        if hash == prefix {
+            return Ok(issue.hash().to_owned());
+        }
+        if hash.starts_with(&prefix) {
+            candidates.push(issue);
+        }
+    }
+
+    match candidates.len() {
+        0 => Err(format!("no issue matches {}", prefix)),
+        1 => Ok(candidates[0].hash().to_owned()),
+        _ => {
+            let candidates: Vec<String> = candidates
+                .iter()
+                .map(|issue| format!("  {} {}", issue.hash(), issue.title()))
+                .collect();
+            Err(format!(
+                "{} is ambiguous, candidates are:\n{}",
+                prefix,
+                candidates.join("\n")
+            ))
+        }
+    }
+}
+
+/// Returns the shortest prefix of hash which no other issue shares
+pub fn abbreviation(handler: &IssuesHandler, hash: &IssueHash) -> String {
+    let hash = hash.to_string();
+    let len = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .map(|(_, issue)| issue.hash().to_string())
+        .filter(|other| *other != hash)
+        .map(|other| {
+            let common = hash
+                .chars()
+                .zip(other.chars())
+                .take_while(|(a, b)| a == b)
+                .count();
+            common + 1
+        })
+        .max()
+        .unwrap_or(0)
+        .max(MIN_ABBREV_LEN);
+
+    hash.chars().take(len).collect()
+}
+
+/// Returns the shortest abbreviation length which keeps all issue hashes distinct
+pub fn abbreviation_length(handler: &IssuesHandler) -> usize {
+    let mut hashes: Vec<String> = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .map(|(_, issue)| issue.hash().to_string())
+        .collect();
+    hashes.sort();
+
+    // the longest common prefix is shared by neighbours in sorted order
+    hashes
+        .windows(2)
+        .map(|v| {
+            let common = v[0]
+                .chars()
+                .zip(v[1].chars())
+                .take_while(|(a, b)| a == b)
+                .count();
+            common + 1
+        })
+        .max()
+        .unwrap_or(0)
+        .max(MIN_ABBREV_LEN)
+}
@@ -0,0 +1,169 @@
+extern crate yansi;
+
+use std::str::FromStr;
+
+use super::{Config, IssueKind, IssuePriority, IssueType};
+
+use self::yansi::{Color, Style};
+
+/// Issue types used when the tracker config doesn't declare any, the first one is the default
+const DEFAULT_TYPES: &[(&str, &str)] = &[
+    ("Bug", "default"),
+    ("Feature", "default"),
+    ("Improvement", "default"),
+    ("Task", "default"),
+];
+
+/// Issue priorities used when the tracker config doesn't declare any, ordered from low to high
+const DEFAULT_PRIORITIES: &[(&str, &str)] =
+    &[("Low", "green"), ("Medium", "yellow"), ("High", "red")];
+
+/// Index of the default priority in DEFAULT_PRIORITIES
+const DEFAULT_PRIORITY: usize = 1;
+
+struct Kind {
+    name: String,
+    color: Color,
+}
+
+/// Issue types and priorities of a tracker with their order and colours
+pub struct Kinds {
+    types: Vec<Kind>,
+    default_type: usize,
+    priorities: Vec<Kind>,
+    default_priority: usize,
+}
+
+impl Kinds {
+    pub fn new(config: &Config) -> Kinds {
+        let kinds = |declared: &[IssueKind], defaults: &[(&str, &str)]| {
+            if declared.is_empty() {
+                defaults
+                    .iter()
+                    .map(|(name, color)| Kind {
+                        name: name.to_string(),
+                        color: color_parser(color),
+                    })
+                    .collect::<Vec<Kind>>()
+            } else {
+                declared
+                    .iter()
+                    .map(|kind| Kind {
+                        name: kind.name().to_string(),
+                        color: kind
+                            .color()
+                            .as_ref()
+                            .map(|v| color_parser(v))
+                            .unwrap_or(Color::Default),
+                    })
+                    .collect()
+            }
+        };
+        let default = |declared: &[IssueKind], fallback: usize| {
+            if declared.is_empty() {
+                fallback
+            } else {
+                declared.iter().position(|v| v.default()).unwrap_or(0)
+            }
+        };
+
+        Kinds {
+            types: kinds(config.issue_types(), DEFAULT_TYPES),
+            default_type: default(config.issue_types(), 0),
+            priorities: kinds(config.priorities(), DEFAULT_PRIORITIES),
+            default_priority: default(config.priorities(), DEFAULT_PRIORITY),
+        }
+    }
+
+    /// Names of all issue types in their configured order
+    pub fn types(&self) -> Vec<&str> {
+        self.types.iter().map(|v| v.name.as_str()).collect()
+    }
+
+    /// Names of all priorities ordered from low to high
+    pub fn priorities(&self) -> Vec<&str> {
+        self.priorities.iter().map(|v| v.name.as_str()).collect()
+    }
+
+    /// Default issue type, fails for configured names the issue types don't know
+    pub fn default_type(&self) -> Result<IssueType, String> {
+        let name = &self.types[self.default_type].name;
+        IssueType::from_str(name).map_err(|e| format!("invalid issue type '{}': {}", name, e))
+    }
+
+    /// Default priority, fails for configured names the priorities don't know
+    pub fn default_priority(&self) -> Result<IssuePriority, String> {
+        let name = &self.priorities[self.default_priority].name;
+        IssuePriority::from_str(name)
+            .map_err(|e| format!("invalid issue priority '{}': {}", name, e))
+    }
+
+    /// Parses an issue type, names are matched ignoring case, '_' and '-'
+    pub fn type_parser(&self, v: &str) -> Result<IssueType, String> {
+        match position(&self.types, v) {
+            Some(i) => IssueType::from_str(&self.types[i].name).map_err(|e| e.to_string()),
+            None => Err(format!(
+                "unknown issue type '{}', possible values: {}",
+                v,
+                self.types().join(", ")
+            )),
+        }
+    }
+
+    /// Parses an issue priority, names are matched ignoring case, '_' and '-'
+    pub fn priority_parser(&self, v: &str) -> Result<IssuePriority, String> {
+        match position(&self.priorities, v) {
+            Some(i) => IssuePriority::from_str(&self.priorities[i].name).map_err(|e| e.to_string()),
+            None => Err(format!(
+                "unknown issue priority '{}', possible values: {}",
+                v,
+                self.priorities().join(", ")
+            )),
+        }
+    }
+
+    /// Position of the priority from low to high, unknown priorities rank lowest
+    pub fn priority_rank(&self, priority: &IssuePriority) -> usize {
+        position(&self.priorities, &priority.to_string())
+            .map(|v| v + 1)
+            .unwrap_or(0)
+    }
+
+    /// Style of the given issue type name
+    pub fn type_style(&self, typ: &str) -> Style {
+        style(&self.types, typ)
+    }
+
+    /// Style of the given priority name
+    pub fn priority_style(&self, priority: &str) -> Style {
+        style(&self.priorities, priority)
+    }
+}
+
+fn position(kinds: &[Kind], v: &str) -> Option<usize> {
+    let normalise = |v: &str| v.replace(&['_', '-', ' '][..], "").to_lowercase();
+    let v = normalise(v);
+    kinds.iter().position(|kind| normalise(&kind.name) == v)
+}
+
+fn style(kinds: &[Kind], v: &str) -> Style {
+    match position(kinds, v) {
+        Some(i) => Style::new(kinds[i].color),
+        None => Style::new(Color::Default),
+    }
+}
+
+/// Parses colour names like 'red' or 256 colour numbers like '208'
+fn color_parser(v: &str) -> Color {
+    match v.trim().to_lowercase().as_ref() {
+        "black" => Color::Black,
+        "red" => Color::Red,
+        "green" => Color::Green,
+        "yellow" => Color::Yellow,
+        "blue" => Color::Blue,
+        "magenta" => Color::Magenta,
+        "cyan" => Color::Cyan,
+        "white" => Color::White,
+        v => v.parse::<u8>().map(Color::Fixed).unwrap_or(Color::Default),
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::BTreeSet;
+
+use super::{Issue, IssueFilter, IssueHash, IssueLinkKind, IssueStatus, IssuesHandler};
+
+/// Link types accepted by link and unlink, blocked-by is stored as blocks on the other issue
+pub const LINK_TYPES: &[&str] = &["blocks", "blocked-by", "relates-to", "duplicate-of"];
+
+/// Parses a link type into the stored kind and whether source and target are swapped
+pub fn kind(v: &str) -> Result<(IssueLinkKind, bool), String> {
+    match v.trim().to_lowercase().replace('_', "-").as_ref() {
+        "blocks" => Ok((IssueLinkKind::Blocks, false)),
+        "blocked-by" => Ok((IssueLinkKind::Blocks, true)),
+        "relates-to" => Ok((IssueLinkKind::RelatesTo, false)),
+        "duplicate-of" => Ok((IssueLinkKind::DuplicateOf, false)),
+        _ => Err(format!(
+            "unknown link type '{}', possible values: {}",
+            v,
+            LINK_TYPES.join(", ")
+        )),
+    }
+}
+
+/// Name of a link kind as given to link and unlink
+pub fn name(kind: IssueLinkKind) -> &'static str {
+    match kind {
+        IssueLinkKind::Blocks => "blocks",
+        IssueLinkKind::RelatesTo => "relates-to",
+        IssueLinkKind::DuplicateOf => "duplicate-of",
+    }
+}
+
+/// Returns whether a link of kind from source to target would close a cycle, relates-to links
+/// may form cycles
+pub fn creates_cycle(
+    handler: &IssuesHandler,
+    kind: IssueLinkKind,
+    source: &IssueHash,
+    target: &IssueHash,
+) -> bool {
+    let links = |hash: &IssueHash| match handler.issue(hash) {
+        Some(issue) => issue
+            .links()
+            .iter()
+            .map(|link| (link.kind(), link.target().to_owned()))
+            .collect(),
+        None => Vec::new(),
+    };
+    closes_cycle(&links, kind, source, target)
+}
+
+/// Returns whether a link closes a cycle given the links of each issue
+fn closes_cycle(
+    links: &dyn Fn(&IssueHash) -> Vec<(IssueLinkKind, IssueHash)>,
+    kind: IssueLinkKind,
+    source: &IssueHash,
+    target: &IssueHash,
+) -> bool {
+    if kind == IssueLinkKind::RelatesTo {
+        return false;
+    }
+
+    // walk the links of the same kind starting at target looking for source
+    let source = source.to_string();
+    let mut visited = BTreeSet::new();
+    let mut stack = vec![target.to_owned()];
+    while let Some(hash) = stack.pop() {
+        if hash.to_string() == source {
+            return true;
+        }
+        if !visited.insert(hash.to_string()) {
+            continue;
+        }
+        for (v, target) in links(&hash) {
+            if v == kind {
+                stack.push(target);
+            }
+        }
+    }
+    false
+}
+
+/// Relations of an issue in both directions, e.g. ("blocked by", blocking issue)
+pub fn relations<'a>(handler: &'a IssuesHandler, issue: &Issue) -> Vec<(&'static str, &'a Issue)> {
+    let mut relations = Vec::new();
+    for link in issue.links() {
+        if let Some(target) = handler.issue(link.target()) {
+            let relation = match link.kind() {
+                IssueLinkKind::Blocks => "blocks",
+                IssueLinkKind::RelatesTo => "relates to",
+                IssueLinkKind::DuplicateOf => "duplicate of",
+            };
+            relations.push((relation, target));
+        }
+    }
+
+    let hash = issue.hash().to_string();
+    for (_, other) in handler.issues_filtered(&IssueFilter::new()) {
+        for link in other.links() {
+            if link.target().to_string() != hash {
+                continue;
+            }
+            let relation = match link.kind() {
+                IssueLinkKind::Blocks => "blocked by",
+                IssueLinkKind::RelatesTo => "relates to",
+                IssueLinkKind::DuplicateOf => "duplicated by",
+            };
+            relations.push((relation, other));
+        }
+    }
+    relations
+}
+
+/// Returns the hashes of all issues blocked by at least one issue which is neither closed nor
+/// rejected
+pub fn blocked(handler: &IssuesHandler) -> BTreeSet<String> {
+    let mut blocked = BTreeSet::new();
+    for (_, issue) in handler.issues_filtered(&IssueFilter::new()) {
+        if matches!(*issue.status(), IssueStatus::Closed | IssueStatus::Rejected) {
+            continue;
+        }
+        for link in issue.links() {
+            if link.kind() == IssueLinkKind::Blocks {
+                blocked.insert(link.target().to_string());
+            }
+        }
+    }
+    blocked
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    fn hash(v: &str) -> IssueHash {
+        IssueHash::from_str(v).unwrap()
+    }
+
+    /// a blocks b, b blocks c, c relates to a, c is a duplicate of d
+    fn links(issue: &IssueHash) -> Vec<(IssueLinkKind, IssueHash)> {
+        match issue.to_string().as_ref() {
+            "deadbeefdeadbeea" => vec![(IssueLinkKind::Blocks, hash("deadbeefdeadbeeb"))],
+            "deadbeefdeadbeeb" => vec![(IssueLinkKind::Blocks, hash("deadbeefdeadbeec"))],
+            "deadbeefdeadbeec" => vec![
+                (IssueLinkKind::RelatesTo, hash("deadbeefdeadbeea")),
+                (IssueLinkKind::DuplicateOf, hash("deadbeefdeadbeed")),
+            ],
+            _ => vec![],
+        }
+    }
+
+    #[test]
+    fn cycles_of_same_kind() {
+        let (a, c) = (hash("deadbeefdeadbeea"), hash("deadbeefdeadbeec"));
+        assert!(closes_cycle(&links, IssueLinkKind::Blocks, &c, &a));
+        assert!(closes_cycle(&links, IssueLinkKind::Blocks, &a, &a));
+        assert!(!closes_cycle(&links, IssueLinkKind::Blocks, &a, &c));
+        // c reaches a only through a relates-to link
+        assert!(!closes_cycle(&links, IssueLinkKind::DuplicateOf, &c, &a));
+    }
+
+    #[test]
+    fn relates_to_never_cycles() {
+        let (a, c) = (hash("deadbeefdeadbeea"), hash("deadbeefdeadbeec"));
+        assert!(!closes_cycle(&links, IssueLinkKind::RelatesTo, &c, &a));
+        assert!(!closes_cycle(&links, IssueLinkKind::RelatesTo, &a, &a));
+    }
+}
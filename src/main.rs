@@ -2,8 +2,26 @@ use std::env;
 use std::process;
 
 mod arguments;
+mod attachments;
+mod board;
+mod commits;
+mod custom;
+mod dates;
 mod display;
+mod fields;
+mod hashes;
+mod kinds;
+mod links;
+mod milestones;
+mod output;
+mod query;
+mod search;
+mod settings;
+mod stats;
 mod subcommands;
+mod tui;
+mod workflow;
+mod worklog;
 
 use cobweb_core::*;
 
@@ -19,8 +37,28 @@ fn main() {
         .subcommand(arguments::open().display_order(2))
         .subcommand(arguments::edit().display_order(3))
         .subcommand(arguments::close().display_order(4))
-        .subcommand(arguments::remove().display_order(5))
-        .subcommand(arguments::config().display_order(6))
+        .subcommand(arguments::reopen().display_order(5))
+        .subcommand(arguments::remove().display_order(6))
+        .subcommand(arguments::comment().display_order(7))
+        .subcommand(arguments::history().display_order(8))
+        .subcommand(arguments::export().display_order(9))
+        .subcommand(arguments::import().display_order(10))
+        .subcommand(arguments::tree().display_order(11))
+        .subcommand(arguments::sync_commits().display_order(12))
+        .subcommand(arguments::hooks().display_order(13))
+        .subcommand(arguments::log().display_order(14))
+        .subcommand(arguments::timer().display_order(15))
+        .subcommand(arguments::time().display_order(16))
+        .subcommand(arguments::milestone().display_order(17))
+        .subcommand(arguments::link().display_order(18))
+        .subcommand(arguments::unlink().display_order(19))
+        .subcommand(arguments::attach().display_order(20))
+        .subcommand(arguments::detach().display_order(21))
+        .subcommand(arguments::search().display_order(22))
+        .subcommand(arguments::tui().display_order(23))
+        .subcommand(arguments::board().display_order(24))
+        .subcommand(arguments::stats().display_order(25))
+        .subcommand(arguments::config().display_order(26))
         .get_matches();
 
     let working_dir = match env::current_dir() {
@@ -51,10 +89,90 @@ fn main() {
         subcommands::close(args, &working_dir);
     }
 
+    if let Some(args) = args.subcommand_matches("reopen") {
+        subcommands::reopen(args, &working_dir);
+    }
+
     if let Some(args) = args.subcommand_matches("remove") {
         subcommands::remove(args, &working_dir);
     }
 
+    if let Some(args) = args.subcommand_matches("comment") {
+        subcommands::comment(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("history") {
+        subcommands::history(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("export") {
+        subcommands::export(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("import") {
+        subcommands::import(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("tree") {
+        subcommands::tree(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("sync-commits") {
+        subcommands::sync_commits(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("hooks") {
+        subcommands::hooks(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("log") {
+        subcommands::log(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("timer") {
+        subcommands::timer(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("time") {
+        subcommands::time(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("milestone") {
+        subcommands::milestone(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("link") {
+        subcommands::link(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("unlink") {
+        subcommands::unlink(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("attach") {
+        subcommands::attach(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("detach") {
+        subcommands::detach(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("search") {
+        subcommands::search(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("tui") {
+        subcommands::tui(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("board") {
+        subcommands::board(args, &working_dir);
+    }
+
+    if let Some(args) = args.subcommand_matches("stats") {
+        subcommands::stats(args, &working_dir);
+    }
+
     if let Some(args) = args.subcommand_matches("config") {
         subcommands::config(args, &working_dir);
     }
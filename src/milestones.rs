@@ -0,0 +1,41 @@
+use super::{Issue, IssueStatus, Milestone};
+
+/// Issue counts and aggregate progress of a milestone
+pub struct Summary<'a> {
+    pub milestone: &'a Milestone,
+    pub open: usize,
+    pub closed: usize,
+    pub progress: u8,
+    pub overdue: bool,
+}
+
+/// Summarises the issues assigned to a milestone, closed and rejected issues count as closed.
+/// A milestone is overdue when its target date passed with open issues left.
+pub fn summary<'a>(milestone: &'a Milestone, issues: &[&Issue], now: i64) -> Summary<'a> {
+    let closed = issues
+        .iter()
+        .filter(|issue| matches!(*issue.status(), IssueStatus::Closed | IssueStatus::Rejected))
+        .count();
+    let open = issues.len() - closed;
+
+    // rejected issues don't contribute to the progress
+    let values: Vec<u32> = issues
+        .iter()
+        .filter(|issue| *issue.status() != IssueStatus::Rejected)
+        .map(|issue| issue.progress() as u32)
+        .collect();
+    let progress = if values.is_empty() {
+        0
+    } else {
+        ((values.iter().sum::<u32>() as f64 / values.len() as f64).round() as u8).min(100)
+    };
+    let overdue = open > 0 && matches!(*milestone.target_date(), Some(v) if v < now);
+
+    Summary {
+        milestone,
+        open,
+        closed,
+        progress,
+        overdue,
+    }
+}
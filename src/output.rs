@@ -0,0 +1,353 @@
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
+use chrono::{Local, TimeZone};
+use serde_json::{json, Map, Value};
+
+use cobweb_core::Issue;
+
+use super::links;
+use super::settings::{Settings, KEYS};
+use super::stats::{Durations, Stats};
+
+/// Machine readable output formats
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Format {
+    Text,
+    Json,
+    Ndjson,
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Format, String> {
+        match s.to_lowercase().as_ref() {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "ndjson" => Ok(Format::Ndjson),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format '{}'", s)),
+        }
+    }
+}
+
+pub const FORMATS: &[&str] = &["text", "json", "ndjson", "csv"];
+
+const ISSUE_COLUMNS: &[&str] = &[
+    "hash",
+    "title",
+    "type",
+    "parent",
+    "author",
+    "creation_date",
+    "assigned_to",
+    "milestone",
+    "start_date",
+    "due_date",
+    "status",
+    "priority",
+    "progress",
+    "progress_rollup",
+    "estimate",
+    "logged",
+    "labels",
+    "fields",
+    "description",
+    "commits",
+    "comments",
+    "changes",
+];
+
+pub fn issues(issues: &[&Issue], format: Format) {
+    match format {
+        Format::Text => {}
+        Format::Json => {
+            let issues: Vec<Value> = issues.iter().map(|issue| issue_json(issue)).collect();
+            println!("{}", pretty(&Value::Array(issues)));
+        }
+        Format::Ndjson => {
+            for issue in issues {
+                println!("{}", issue_json(issue));
+            }
+        }
+        Format::Csv => {
+            println!("{}", csv_row(ISSUE_COLUMNS.iter().map(|v| v.to_string())));
+            for issue in issues {
+                println!("{}", csv_row(issue_csv(issue)));
+            }
+        }
+    }
+}
+
+pub fn config(settings: &Settings, format: Format) {
+    let mut values = Map::new();
+    for (key, _) in KEYS {
+        let value = match settings.get(key) {
+            Some((value, layer)) => json!({ "value": value, "layer": layer.to_string() }),
+            None => Value::Null,
+        };
+        values.insert(key.to_string(), value);
+    }
+    match format {
+        Format::Text => {}
+        Format::Json => println!("{}", pretty(&Value::Object(values))),
+        Format::Ndjson => println!("{}", Value::Object(values)),
+        Format::Csv => {
+            println!(
+                "{}",
+                csv_row(vec![
+                    "key".to_string(),
+                    "value".to_string(),
+                    "layer".to_string()
+                ])
+            );
+            for (key, _) in KEYS {
+                let (value, layer) = match settings.get(key) {
+                    Some((value, layer)) => (value.to_string(), layer.to_string()),
+                    None => (String::new(), String::new()),
+                };
+                println!("{}", csv_row(vec![key.to_string(), value, layer]));
+            }
+        }
+    }
+}
+
+pub fn stats(stats: &Stats, format: Format) {
+    let durations = |v: &Option<Durations>| match v {
+        Some(v) => json!({
+            "count": v.count,
+            "mean": v.mean,
+            "median": v.median,
+            "p85": v.p85,
+        }),
+        None => Value::Null,
+    };
+    let mut values = Map::new();
+    values.insert("total".to_string(), json!(stats.total));
+    for (name, counts) in &stats.groups {
+        let counts: Map<String, Value> = counts
+            .iter()
+            .map(|(key, count)| (key.to_string(), json!(count)))
+            .collect();
+        values.insert(name.to_string(), Value::Object(counts));
+    }
+    values.insert("lead_time".to_string(), durations(&stats.lead_time));
+    values.insert("cycle_time".to_string(), durations(&stats.cycle_time));
+    values.insert("undated".to_string(), json!(stats.undated));
+    let throughput: Map<String, Value> = stats
+        .throughput
+        .iter()
+        .map(|(week, count)| (week.to_string(), json!(count)))
+        .collect();
+    values.insert("throughput".to_string(), Value::Object(throughput));
+
+    match format {
+        Format::Text => {}
+        Format::Json => println!("{}", pretty(&Value::Object(values))),
+        Format::Ndjson => println!("{}", Value::Object(values)),
+        Format::Csv => {
+            // one row per value, durations in seconds
+            let row = |group: &str, key: &str, value: String| {
+                println!(
+                    "{}",
+                    csv_row(vec![group.to_string(), key.to_string(), value])
+                );
+            };
+            row("group", "key", "value".to_string());
+            row("total", "", stats.total.to_string());
+            for (name, counts) in &stats.groups {
+                for (key, count) in counts {
+                    row(name, key, count.to_string());
+                }
+            }
+            for (name, v) in &[
+                ("lead_time", &stats.lead_time),
+                ("cycle_time", &stats.cycle_time),
+            ] {
+                if let Some(v) = v {
+                    row(name, "count", v.count.to_string());
+                    row(name, "mean", v.mean.to_string());
+                    row(name, "median", v.median.to_string());
+                    row(name, "p85", v.p85.to_string());
+                }
+            }
+            row("undated", "", stats.undated.to_string());
+            for (week, count) in &stats.throughput {
+                row("throughput", week, count.to_string());
+            }
+        }
+    }
+}
+
+/// Version of the archive format written by export, version 2 added attachment contents
+pub const ARCHIVE_VERSION: u64 = 2;
+
+/// Serialises issues and the base64 encoded attachment contents keyed by their digest
+pub fn archive(
+    issues: &[&Issue],
+    contents: &BTreeMap<String, Vec<u8>>,
+) -> Result<String, serde_json::Error> {
+    let issues = issues
+        .iter()
+        .map(serde_json::to_value)
+        .collect::<Result<Vec<Value>, _>>()?;
+    let contents: Map<String, Value> = contents
+        .iter()
+        .map(|(digest, data)| (digest.to_string(), json!(base64::encode(data))))
+        .collect();
+    let archive = json!({
+        "version": ARCHIVE_VERSION,
+        "issues": issues,
+        "attachments": contents,
+    });
+    serde_json::to_string_pretty(&archive)
+}
+
+pub fn issue_json(issue: &Issue) -> Value {
+    let comments: Vec<Value> = issue
+        .comments()
+        .iter()
+        .map(|comment| {
+            json!({
+                "author": comment.author(),
+                "creation_date": date(comment.creation_date()),
+                "text": comment.text(),
+            })
+        })
+        .collect();
+    let work_logs: Vec<Value> = issue
+        .work_logs()
+        .iter()
+        .map(|log| {
+            json!({
+                "author": log.author(),
+                "date": date(log.date()),
+                "duration": log.duration(),
+            })
+        })
+        .collect();
+    let attachments: Vec<Value> = issue
+        .attachments()
+        .iter()
+        .map(|attachment| {
+            json!({
+                "name": attachment.name(),
+                "digest": attachment.digest(),
+                "size": attachment.size(),
+            })
+        })
+        .collect();
+    let links: Vec<Value> = issue
+        .links()
+        .iter()
+        .map(|link| {
+            json!({
+                "kind": links::name(link.kind()),
+                "target": link.target().to_string(),
+            })
+        })
+        .collect();
+    let changes: Vec<Value> = issue
+        .history()
+        .iter()
+        .map(|change| {
+            json!({
+                "field": change.field(),
+                "old_value": change.old_value(),
+                "new_value": change.new_value(),
+                "author": change.author(),
+                "creation_date": date(change.creation_date()),
+            })
+        })
+        .collect();
+
+    json!({
+        "hash": issue.hash().to_string(),
+        "title": issue.title(),
+        "type": issue.typ().to_string(),
+        "parent": issue.parent().as_ref().map(|v| v.to_string()),
+        "author": issue.author(),
+        "creation_date": date(issue.creation_date()),
+        "assigned_to": issue.assigned_to(),
+        "milestone": issue.milestone(),
+        "start_date": date(issue.start_date()),
+        "due_date": issue.due_date().map(date),
+        "status": issue.status().to_string(),
+        "priority": issue.priority().to_string(),
+        "progress": issue.progress(),
+        "progress_rollup": issue.progress_rollup(),
+        "estimate": issue.estimate(),
+        "work_logs": work_logs,
+        "labels": issue.labels(),
+        "fields": issue.custom_fields(),
+        "description": issue.description(),
+        "commits": issue.commits(),
+        "attachments": attachments,
+        "links": links,
+        "comments": comments,
+        "changes": changes,
+    })
+}
+
+fn issue_csv(issue: &Issue) -> Vec<String> {
+    let labels: Vec<&str> = issue.labels().iter().map(|v| v.as_str()).collect();
+    let logged: u64 = issue.work_logs().iter().map(|log| log.duration()).sum();
+    let fields: Vec<String> = issue
+        .custom_fields()
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect();
+    vec![
+        issue.hash().to_string(),
+        issue.title().to_string(),
+        issue.typ().to_string(),
+        issue
+            .parent()
+            .as_ref()
+            .map(|v| v.to_string())
+            .unwrap_or_default(),
+        issue.author().to_string(),
+        date(issue.creation_date()),
+        issue.assigned_to().to_owned().unwrap_or_default(),
+        issue.milestone().to_owned().unwrap_or_default(),
+        date(issue.start_date()),
+        issue.due_date().map(date).unwrap_or_default(),
+        issue.status().to_string(),
+        issue.priority().to_string(),
+        issue.progress().to_string(),
+        issue.progress_rollup().to_string(),
+        issue.estimate().map(|v| v.to_string()).unwrap_or_default(),
+        logged.to_string(),
+        labels.join(";"),
+        fields.join(";"),
+        issue.description().to_owned().unwrap_or_default(),
+        issue.commits().join(";"),
+        issue.comments().len().to_string(),
+        issue.history().len().to_string(),
+    ]
+}
+
+/// ISO-8601 representation of a timestamp in local time
+pub fn date(ts: i64) -> String {
+    Local.timestamp(ts, 0).to_rfc3339()
+}
+
+fn pretty(value: &Value) -> String {
+    serde_json::to_string_pretty(value).unwrap_or_else(|_| value.to_string())
+}
+
+fn csv_row<I: IntoIterator<Item = String>>(fields: I) -> String {
+    let fields: Vec<String> = fields
+        .into_iter()
+        .map(|v| {
+            if v.contains(&[',', '"', '\n', '\r'][..]) {
+                format!("\"{}\"", v.replace('"', "\"\""))
+            } else {
+                v
+            }
+        })
+        .collect();
+    fields.join(",")
+}
@@ -0,0 +1,568 @@
+use std::collections::BTreeSet;
+use std::str::FromStr;
+
+use chrono::Local;
+
+use super::dates;
+use super::hashes;
+use super::kinds::Kinds;
+use super::{IssueFilter, IssueStatus, IssuesHandler};
+
+/* Query grammar, operators are case insensitive:
+   query  := or
+   or     := and { "or" and }
+   and    := not { ["and"] not }
+   not    := "not" not | "(" or ")" | term
+   term   := field (":" | "<" | "<=" | ">" | ">=") value
+   value  := word | "quoted words"
+
+   Example:
+   (status:open or status:in_progress) and priority:high and not assigned:bob and due<2026-11-01
+   due<="end of month" and created>-2w
+*/
+
+pub enum Query {
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+    Not(Box<Query>),
+    Term(IssueFilter),
+    /// matches no issue, e.g. progress>100
+    Nothing,
+}
+
+impl Query {
+    pub fn parse(query: &str, handler: &IssuesHandler, kinds: &Kinds) -> Result<Query, String> {
+        let tokens = tokenize(query)?;
+        let term = |field: &str, op: &Operator, value: &str| term(field, op, value, handler, kinds);
+        let mut parser = Parser {
+            tokens,
+            pos: 0,
+            term: &term,
+        };
+        let query = parser.or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(v) => Err(format!("unexpected token '{}'", v)),
+        }
+    }
+
+    /// Returns hashes of all issues matching the query
+    pub fn evaluate(&self, handler: &IssuesHandler) -> BTreeSet<String> {
+        match *self {
+            Query::And(ref a, ref b) => {
+                let a = a.evaluate(handler);
+                let b = b.evaluate(handler);
+                a.intersection(&b).cloned().collect()
+            }
+            Query::Or(ref a, ref b) => {
+                let a = a.evaluate(handler);
+                let b = b.evaluate(handler);
+                a.union(&b).cloned().collect()
+            }
+            Query::Not(ref a) => {
+                let all = Query::Term(IssueFilter::new()).evaluate(handler);
+                let a = a.evaluate(handler);
+                all.difference(&a).cloned().collect()
+            }
+            Query::Term(ref filter) => handler
+                .issues_filtered(filter)
+                .into_iter()
+                .map(|(_, issue)| issue.hash().to_string())
+                .collect(),
+            Query::Nothing => BTreeSet::new(),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Token {
+    Open,
+    Close,
+    Word(String),
+    Term(String, Operator, String),
+}
+
+impl std::fmt::Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match *self {
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Word(ref v) => write!(f, "{}", v),
+            Token::Term(ref field, ref op, ref value) => write!(f, "{}{}{}", field, op, value),
+        }
+    }
+}
+
+#[derive(PartialEq)]
+enum Operator {
+    Equal,
+    Less,
+    LessEqual,
+    Greater,
+    GreaterEqual,
+}
+
+impl std::fmt::Display for Operator {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        let op = match *self {
+            Operator::Equal => ":",
+            Operator::Less => "<",
+            Operator::LessEqual => "<=",
+            Operator::Greater => ">",
+            Operator::GreaterEqual => ">=",
+        };
+        write!(f, "{}", op)
+    }
+}
+
+fn tokenize(query: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = query.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        if c == '(' {
+            chars.next();
+            tokens.push(Token::Open);
+            continue;
+        }
+        if c == ')' {
+            chars.next();
+            tokens.push(Token::Close);
+            continue;
+        }
+
+        // field name or operator keyword
+        let mut field = String::new();
+        while let Some(&c) = chars.peek() {
+            if c.is_whitespace() || c == '(' || c == ')' || c == ':' || c == '<' || c == '>' {
+                break;
+            }
+            field.push(c);
+            chars.next();
+        }
+
+        let op = match chars.peek() {
+            Some(':') => Operator::Equal,
+            Some('<') => Operator::Less,
+            Some('>') => Operator::Greater,
+            _ => {
+                if field.is_empty() {
+                    return Err(format!("unexpected character '{}'", c));
+                }
+                tokens.push(Token::Word(field));
+                continue;
+            }
+        };
+        chars.next();
+        let op = match (op, chars.peek()) {
+            (Operator::Less, Some('=')) => {
+                chars.next();
+                Operator::LessEqual
+            }
+            (Operator::Greater, Some('=')) => {
+                chars.next();
+                Operator::GreaterEqual
+            }
+            (op, _) => op,
+        };
+        if field.is_empty() {
+            return Err(format!("missing field name before '{}'", op));
+        }
+
+        // value, optionally quoted to allow whitespaces and parentheses
+        let mut value = String::new();
+        if let Some('"') = chars.peek() {
+            chars.next();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(c) => value.push(c),
+                    None => return Err(format!("unterminated quote in value of '{}'", field)),
+                }
+            }
+        } else {
+            while let Some(&c) = chars.peek() {
+                if c.is_whitespace() || c == '(' || c == ')' {
+                    break;
+                }
+                value.push(c);
+                chars.next();
+            }
+        }
+        if value.is_empty() {
+            return Err(format!("missing value for '{}{}'", field, op));
+        }
+
+        tokens.push(Token::Term(field.to_lowercase(), op, value));
+    }
+
+    Ok(tokens)
+}
+
+/// Builds the query of a single term
+type TermBuilder<'a> = &'a dyn Fn(&str, &Operator, &str) -> Result<Query, String>;
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    pos: usize,
+    term: TermBuilder<'a>,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn keyword(&self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(Token::Word(ref v)) => v.eq_ignore_ascii_case(keyword),
+            _ => false,
+        }
+    }
+
+    fn or(&mut self) -> Result<Query, String> {
+        let mut query = self.and()?;
+        while self.keyword("or") {
+            self.pos += 1;
+            let rhs = self.and()?;
+            query = Query::Or(Box::new(query), Box::new(rhs));
+        }
+        Ok(query)
+    }
+
+    fn and(&mut self) -> Result<Query, String> {
+        let mut query = self.not()?;
+        loop {
+            if self.keyword("and") {
+                self.pos += 1;
+            } else if self.keyword("or") {
+                break;
+            } else {
+                // adjacent terms are implicitly combined with and
+                match self.peek() {
+                    None | Some(Token::Close) => break,
+                    _ => {}
+                }
+            }
+            let rhs = self.not()?;
+            query = Query::And(Box::new(query), Box::new(rhs));
+        }
+        Ok(query)
+    }
+
+    fn not(&mut self) -> Result<Query, String> {
+        if self.keyword("not") {
+            self.pos += 1;
+            let query = self.not()?;
+            return Ok(Query::Not(Box::new(query)));
+        }
+
+        let pos = self.pos;
+        self.pos += 1;
+        match self.tokens.get(pos) {
+            Some(Token::Open) => {
+                let query = self.or()?;
+                match self.peek() {
+                    Some(Token::Close) => {
+                        self.pos += 1;
+                        Ok(query)
+                    }
+                    _ => Err("missing closing parenthesis".to_string()),
+                }
+            }
+            Some(Token::Term(ref field, ref op, ref value)) => (self.term)(field, op, value),
+            Some(v) => Err(format!("unexpected token '{}'", v)),
+            None => Err("unexpected end of query".to_string()),
+        }
+    }
+}
+
+fn term(
+    field: &str,
+    op: &Operator,
+    value: &str,
+    handler: &IssuesHandler,
+    kinds: &Kinds,
+) -> Result<Query, String> {
+    let mut filter = IssueFilter::new();
+
+    match field {
+        "hash" => {
+            equal_only(field, op)?;
+            let hash = hashes::resolve(handler, value)?;
+            filter.set_hash_match(hash);
+        }
+        "type" => {
+            equal_only(field, op)?;
+            let typ = kinds.type_parser(value)?;
+            filter.set_type_match(typ);
+        }
+        "parent" => {
+            equal_only(field, op)?;
+            let hash = hashes::resolve(handler, value)?;
+            filter.set_parent_match(hash);
+        }
+        "author" => {
+            equal_only(field, op)?;
+            filter
+                .set_autor_match(value)
+                .map_err(|e| format!("invalid author pattern: {}, {}", value, e))?;
+        }
+        "title" => {
+            equal_only(field, op)?;
+            filter
+                .set_title_match(value)
+                .map_err(|e| format!("invalid title pattern: {}, {}", value, e))?;
+        }
+        "description" => {
+            equal_only(field, op)?;
+            filter
+                .set_description_match(value)
+                .map_err(|e| format!("invalid description pattern: {}, {}", value, e))?;
+        }
+        "priority" => {
+            equal_only(field, op)?;
+            let priority = kinds.priority_parser(value)?;
+            filter.set_priority_match(priority);
+        }
+        "status" => {
+            equal_only(field, op)?;
+            let status = IssueStatus::from_str(&variant(value))
+                .map_err(|e| format!("invalid issue status: {}, {}", value, e))?;
+            filter.set_status_match(status);
+        }
+        "assigned" | "assigned_to" => {
+            equal_only(field, op)?;
+            filter
+                .set_assigned_to_match(value)
+                .map_err(|e| format!("invalid assigned to pattern: {}, {}", value, e))?;
+        }
+        "tag" | "label" => {
+            equal_only(field, op)?;
+            filter.add_label_match(value.to_string());
+        }
+        "created" | "creation" => {
+            let (from, to) = range(op, date_range(value)?);
+            if let Some(v) = from {
+                filter.set_creation_date_from_match(v);
+            }
+            if let Some(v) = to {
+                filter.set_creation_date_to_match(v);
+            }
+        }
+        "start" => {
+            let (from, to) = range(op, date_range(value)?);
+            if let Some(v) = from {
+                filter.set_start_date_from_match(v);
+            }
+            if let Some(v) = to {
+                filter.set_start_date_to_match(v);
+            }
+        }
+        "due" => {
+            let (from, to) = range(op, date_range(value)?);
+            if let Some(v) = from {
+                filter.set_due_date_from_match(v);
+            }
+            if let Some(v) = to {
+                filter.set_due_date_to_match(v);
+            }
+        }
+        "progress" => match progress_range(op, value)? {
+            Some((from, to)) => {
+                filter
+                    .set_progress_from_match(from)
+                    .map_err(|e| format!("invalid lower progress limit: {}, {}", from, e))?;
+                filter
+                    .set_progress_to_match(to)
+                    .map_err(|e| format!("invalid upper progress limit: {}, {}", to, e))?;
+            }
+            None => return Ok(Query::Nothing),
+        },
+        _ => return Err(format!("unknown field '{}'", field)),
+    }
+
+    Ok(Query::Term(filter))
+}
+
+fn equal_only(field: &str, op: &Operator) -> Result<(), String> {
+    if *op == Operator::Equal {
+        Ok(())
+    } else {
+        Err(format!("field '{}' only supports ':'", field))
+    }
+}
+
+/// Converts query values like in_progress to the variant names like InProgress
+fn variant(value: &str) -> String {
+    value
+        .split(&['_', '-'][..])
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(c) => c.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Converts an inclusive value range into inclusive filter limits for the given operator
+fn range(op: &Operator, (first, last): (i64, i64)) -> (Option<i64>, Option<i64>) {
+    match *op {
+        Operator::Equal => (Some(first), Some(last)),
+        Operator::Less => (None, Some(first - 1)),
+        Operator::LessEqual => (None, Some(last)),
+        Operator::Greater => (Some(last + 1), None),
+        Operator::GreaterEqual => (Some(first), None),
+    }
+}
+
+/// Converts a progress term into inclusive limits, None when no progress matches,
+/// e.g. progress<0 or progress>100
+fn progress_range(op: &Operator, value: &str) -> Result<Option<(u8, u8)>, String> {
+    let progress = value
+        .parse::<i64>()
+        .map_err(|e| format!("invalid progress: {}, {}", value, e))?;
+    if !(0..=100).contains(&progress) {
+        return Err(format!("invalid progress: {}, expected 0 to 100", value));
+    }
+    let (from, to) = range(op, (progress, progress));
+    let (from, to) = (from.unwrap_or(0), to.unwrap_or(100));
+    if from > to {
+        return Ok(None);
+    }
+    Ok(Some((from as u8, to as u8)))
+}
+
+/// Parses a date (whole day) or date time (whole minute) into an inclusive timestamp range
+fn date_range(value: &str) -> Result<(i64, i64), String> {
+    dates::parse(value, Local::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    use super::*;
+
+    /// Parses query with terms recorded instead of built, returns the query with explicit
+    /// parentheses
+    fn parse(query: &str) -> Result<String, String> {
+        let terms = RefCell::new(Vec::new());
+        let term = |field: &str, op: &Operator, value: &str| {
+            terms.borrow_mut().push(format!("{}{}{}", field, op, value));
+            Ok(Query::Term(IssueFilter::new()))
+        };
+        let mut parser = Parser {
+            tokens: tokenize(query)?,
+            pos: 0,
+            term: &term,
+        };
+        let parsed = parser.or()?;
+        if let Some(v) = parser.peek() {
+            return Err(format!("unexpected token '{}'", v));
+        }
+        let terms = terms.into_inner();
+        Ok(render(&parsed, &mut terms.iter()))
+    }
+
+    fn render(query: &Query, terms: &mut std::slice::Iter<String>) -> String {
+        match *query {
+            Query::And(ref a, ref b) => format!("({} and {})", render(a, terms), render(b, terms)),
+            Query::Or(ref a, ref b) => format!("({} or {})", render(a, terms), render(b, terms)),
+            Query::Not(ref a) => format!("not {}", render(a, terms)),
+            Query::Term(_) => terms.next().unwrap().to_string(),
+            Query::Nothing => "nothing".to_string(),
+        }
+    }
+
+    #[test]
+    fn precedence() {
+        assert_eq!(
+            parse("a:1 or b:2 and c:3"),
+            Ok("(a:1 or (b:2 and c:3))".to_string())
+        );
+        assert_eq!(
+            parse("(a:1 or b:2) and c:3"),
+            Ok("((a:1 or b:2) and c:3)".to_string())
+        );
+        assert_eq!(
+            parse("not a:1 and b:2"),
+            Ok("(not a:1 and b:2)".to_string())
+        );
+        assert_eq!(
+            parse("not (a:1 or b:2)"),
+            Ok("not (a:1 or b:2)".to_string())
+        );
+        assert_eq!(parse("NOT not a:1"), Ok("not not a:1".to_string()));
+    }
+
+    #[test]
+    fn implicit_and() {
+        assert_eq!(
+            parse("a:1 b:2 OR c:3"),
+            Ok("((a:1 and b:2) or c:3)".to_string())
+        );
+    }
+
+    #[test]
+    fn operators_and_values() {
+        assert_eq!(
+            parse("Due<=\"end of month\" created>-2w progress>=50 start<2026-11-01"),
+            Ok(
+                "(((due<=end of month and created>-2w) and progress>=50) and start<2026-11-01)"
+                    .to_string()
+            )
+        );
+        assert_eq!(parse("title:\"(a) b\""), Ok("title:(a) b".to_string()));
+    }
+
+    #[test]
+    fn invalid_queries() {
+        for query in &[
+            "", "(a:1", "a:1)", "a:1 and", "or a:1", "a:", ":1", "<1", "a:\"x", "word", "a:1 not",
+        ] {
+            assert!(parse(query).is_err(), "{}", query);
+        }
+    }
+
+    #[test]
+    fn ranges() {
+        assert_eq!(range(&Operator::Equal, (10, 20)), (Some(10), Some(20)));
+        assert_eq!(range(&Operator::Less, (10, 20)), (None, Some(9)));
+        assert_eq!(range(&Operator::LessEqual, (10, 20)), (None, Some(20)));
+        assert_eq!(range(&Operator::Greater, (10, 20)), (Some(21), None));
+        assert_eq!(range(&Operator::GreaterEqual, (10, 20)), (Some(10), None));
+    }
+
+    #[test]
+    fn progress_ranges() {
+        assert_eq!(progress_range(&Operator::Equal, "50"), Ok(Some((50, 50))));
+        assert_eq!(progress_range(&Operator::Less, "50"), Ok(Some((0, 49))));
+        assert_eq!(
+            progress_range(&Operator::Greater, "50"),
+            Ok(Some((51, 100)))
+        );
+        assert_eq!(
+            progress_range(&Operator::GreaterEqual, "100"),
+            Ok(Some((100, 100)))
+        );
+        assert_eq!(progress_range(&Operator::Less, "0"), Ok(None));
+        assert_eq!(progress_range(&Operator::Greater, "100"), Ok(None));
+        assert!(progress_range(&Operator::Equal, "101").is_err());
+        assert!(progress_range(&Operator::Less, "-1").is_err());
+        assert!(progress_range(&Operator::Equal, "half").is_err());
+    }
+
+    #[test]
+    fn variants() {
+        assert_eq!(variant("in_progress"), "InProgress");
+        assert_eq!(variant("in-progress"), "InProgress");
+        assert_eq!(variant("open"), "Open");
+    }
+}
@@ -0,0 +1,587 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use super::Issue;
+
+/// Name of the file in the tracker directory holding the inverted index
+const INDEX_FILE: &str = "search_index.json";
+
+/// Version of the index file, indexes of other versions are rebuilt
+const INDEX_VERSION: u64 = 1;
+
+/// Hits in titles count this many times
+const TITLE_WEIGHT: f64 = 3.0;
+
+/// BM25 parameters
+const K1: f64 = 1.2;
+const B: f64 = 0.75;
+
+/// Words shown before and after the first hit of a snippet
+const SNIPPET_BEFORE: usize = 6;
+const SNIPPET_AFTER: usize = 12;
+
+/// Term of a search query, all terms of a query have to match
+#[derive(PartialEq, Debug)]
+pub enum Term {
+    Word(String),
+    /// e.g. pars* matching parser and parsing
+    Prefix(String),
+    /// e.g. "null pointer", words have to follow each other
+    Phrase(Vec<String>),
+}
+
+/// Parses a query of words, prefixes ending with * and double quoted phrases
+pub fn parse(query: &str) -> Result<Vec<Term>, String> {
+    let mut terms = Vec::new();
+    for (i, part) in query.split('"').enumerate() {
+        if i % 2 == 1 {
+            let words: Vec<String> = tokens(part).into_iter().map(|(_, _, v)| v).collect();
+            match words.len() {
+                0 => {}
+                1 => terms.push(Term::Word(words[0].to_owned())),
+                _ => terms.push(Term::Phrase(words)),
+            }
+            continue;
+        }
+        for word in part.split_whitespace() {
+            let prefix = word.ends_with('*');
+            let words: Vec<String> = tokens(word).into_iter().map(|(_, _, v)| v).collect();
+            for (j, v) in words.iter().enumerate() {
+                if prefix && j == words.len() - 1 {
+                    terms.push(Term::Prefix(v.to_owned()));
+                } else {
+                    terms.push(Term::Word(v.to_owned()));
+                }
+            }
+        }
+    }
+    if query.matches('"').count() % 2 == 1 {
+        return Err("unterminated phrase, missing closing \"".to_string());
+    }
+    if terms.is_empty() {
+        return Err("no search terms given".to_string());
+    }
+    Ok(terms)
+}
+
+/// Splits text into lower case alphanumeric words with their byte ranges
+pub fn tokens(text: &str) -> Vec<(usize, usize, String)> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                tokens.push((s, i, text[s..i].to_lowercase()));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        tokens.push((s, text.len(), text[s..].to_lowercase()));
+    }
+    tokens
+}
+
+/// Searchable texts of an issue, the title comes first
+pub fn sections(issue: &Issue) -> Vec<String> {
+    let mut sections = vec![issue.title().to_string()];
+    if let Some(ref v) = *issue.description() {
+        sections.push(v.to_string());
+    }
+    for comment in issue.comments() {
+        sections.push(comment.text().to_string());
+    }
+    sections
+}
+
+struct Document {
+    fingerprint: String,
+    title_length: usize,
+    length: usize,
+}
+
+/// Inverted index of issue words and the positions they occur at. Positions of the sections
+/// of an issue are separated by a gap so phrases don't match across sections.
+pub struct Index {
+    documents: BTreeMap<String, Document>,
+    postings: BTreeMap<String, BTreeMap<String, Vec<usize>>>,
+}
+
+/// Ranked search result with the words to highlight
+pub struct Hit {
+    pub hash: String,
+    pub score: f64,
+    pub words: BTreeSet<String>,
+}
+
+impl Index {
+    /// Reads the index from the tracker directory, missing or outdated indexes are empty
+    pub fn read(tracker_dir: &Path) -> Result<Index, String> {
+        let mut index = Index {
+            documents: BTreeMap::new(),
+            postings: BTreeMap::new(),
+        };
+
+        let path = tracker_dir.join(INDEX_FILE);
+        let data = match fs::read_to_string(&path) {
+            Ok(v) => v,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(index),
+            Err(e) => return Err(format!("{}: {}", path.display(), e)),
+        };
+        let value: Value = match serde_json::from_str(&data) {
+            Ok(v) => v,
+            // a corrupt index is rebuilt
+            Err(_) => return Ok(index),
+        };
+        if value["version"].as_u64() != Some(INDEX_VERSION) {
+            return Ok(index);
+        }
+
+        if let Some(documents) = value["documents"].as_object() {
+            for (hash, document) in documents {
+                let document = Document {
+                    fingerprint: document["fingerprint"].as_str().unwrap_or("").to_string(),
+                    title_length: document["title_length"].as_u64().unwrap_or(0) as usize,
+                    length: document["length"].as_u64().unwrap_or(0) as usize,
+                };
+                index.documents.insert(hash.to_string(), document);
+            }
+        }
+        if let Some(postings) = value["postings"].as_object() {
+            for (word, hashes) in postings {
+                let entry = index.postings.entry(word.to_string()).or_default();
+                for (hash, positions) in hashes.as_object().into_iter().flatten() {
+                    let positions = positions
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter_map(|v| v.as_u64())
+                        .map(|v| v as usize)
+                        .collect();
+                    entry.insert(hash.to_string(), positions);
+                }
+            }
+        }
+        Ok(index)
+    }
+
+    pub fn write(&self, tracker_dir: &Path) -> Result<(), String> {
+        let documents: serde_json::Map<String, Value> = self
+            .documents
+            .iter()
+            .map(|(hash, document)| {
+                let value = json!({
+                    "fingerprint": document.fingerprint,
+                    "title_length": document.title_length,
+                    "length": document.length,
+                });
+                (hash.to_string(), value)
+            })
+            .collect();
+        let value = json!({
+            "version": INDEX_VERSION,
+            "documents": documents,
+            "postings": self.postings,
+        });
+
+        let path = tracker_dir.join(INDEX_FILE);
+        let data = serde_json::to_string(&value).map_err(|e| e.to_string())?;
+        fs::write(&path, data).map_err(|e| format!("{}: {}", path.display(), e))?;
+        ignore(tracker_dir)
+    }
+
+    /// Reindexes new and changed issues and drops removed ones, returns whether the index
+    /// changed
+    pub fn update(&mut self, issues: &[&Issue]) -> bool {
+        let mut changed = Vec::new();
+        let mut current = BTreeSet::new();
+        for issue in issues {
+            let hash = issue.hash().to_string();
+            let sections = sections(issue);
+            let fingerprint = fingerprint(&sections);
+            match self.documents.get(&hash) {
+                Some(v) if v.fingerprint == fingerprint => {}
+                _ => changed.push((hash.clone(), fingerprint, sections)),
+            }
+            current.insert(hash);
+        }
+
+        let stale: BTreeSet<String> = self
+            .documents
+            .keys()
+            .filter(|hash| !current.contains(*hash))
+            .cloned()
+            .chain(changed.iter().map(|(hash, _, _)| hash.clone()))
+            .collect();
+        if stale.is_empty() && changed.is_empty() {
+            return false;
+        }
+
+        // drop stale documents
+        for hash in &stale {
+            self.documents.remove(hash);
+        }
+        for hashes in self.postings.values_mut() {
+            hashes.retain(|hash, _| !stale.contains(hash));
+        }
+        self.postings.retain(|_, hashes| !hashes.is_empty());
+
+        // add new and changed documents
+        for (hash, fingerprint, sections) in changed {
+            self.add(hash, fingerprint, &sections);
+        }
+        true
+    }
+
+    /// Adds the words of the sections of an issue which isn't indexed yet
+    fn add(&mut self, hash: String, fingerprint: String, sections: &[String]) {
+        let mut position = 0;
+        let mut title_length = 0;
+        for (i, section) in sections.iter().enumerate() {
+            for (_, _, word) in tokens(section) {
+                self.postings
+                    .entry(word)
+                    .or_default()
+                    .entry(hash.clone())
+                    .or_default()
+                    .push(position);
+                position += 1;
+            }
+            if i == 0 {
+                title_length = position;
+            }
+            position += 1;
+        }
+        let document = Document {
+            fingerprint,
+            title_length,
+            length: position,
+        };
+        self.documents.insert(hash, document);
+    }
+
+    /// Returns issues matching all terms ranked by BM25 score, title hits weigh more
+    pub fn search(&self, terms: &[Term]) -> Vec<Hit> {
+        let count = self.documents.len() as f64;
+        let average = self
+            .documents
+            .values()
+            .map(|v| v.length as f64)
+            .sum::<f64>()
+            / count.max(1.0);
+
+        let mut hits: Option<BTreeMap<String, Hit>> = None;
+        for term in terms {
+            let (positions, words) = self.positions(term);
+            let df = positions.len() as f64;
+            let idf = (1.0 + (count - df + 0.5) / (df + 0.5)).ln();
+
+            let mut next = BTreeMap::new();
+            for (hash, positions) in positions {
+                let mut hit = match hits {
+                    Some(ref mut v) => match v.remove(&hash) {
+                        Some(v) => v,
+                        None => continue,
+                    },
+                    None => Hit {
+                        hash: hash.clone(),
+                        score: 0.0,
+                        words: BTreeSet::new(),
+                    },
+                };
+                let document = &self.documents[&hash];
+                let tf: f64 = positions
+                    .iter()
+                    .map(|v| {
+                        if *v < document.title_length {
+                            TITLE_WEIGHT
+                        } else {
+                            1.0
+                        }
+                    })
+                    .sum();
+                let norm = 1.0 - B + B * document.length as f64 / average;
+                hit.score += idf * tf * (K1 + 1.0) / (tf + K1 * norm);
+                hit.words.extend(words.iter().cloned());
+                next.insert(hash, hit);
+            }
+            hits = Some(next);
+        }
+
+        let mut hits: Vec<Hit> = hits.unwrap_or_default().into_values().collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .partial_cmp(&a.score)
+                .unwrap_or(std::cmp::Ordering::Equal)
+        });
+        hits
+    }
+
+    /// Positions a term matches at by issue, phrases match at the position of their first word,
+    /// and the indexed words the term matched
+    fn positions(&self, term: &Term) -> (BTreeMap<String, Vec<usize>>, BTreeSet<String>) {
+        let mut positions: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        let mut words = BTreeSet::new();
+        match term {
+            Term::Word(word) => {
+                if let Some(v) = self.postings.get(word) {
+                    positions = v.to_owned();
+                    words.insert(word.to_owned());
+                }
+            }
+            Term::Prefix(prefix) => {
+                let matching = self
+                    .postings
+                    .range(prefix.to_owned()..)
+                    .take_while(|(word, _)| word.starts_with(prefix.as_str()));
+                for (word, hashes) in matching {
+                    for (hash, v) in hashes {
+                        positions.entry(hash.to_owned()).or_default().extend(v);
+                    }
+                    words.insert(word.to_owned());
+                }
+                for v in positions.values_mut() {
+                    v.sort_unstable();
+                }
+            }
+            Term::Phrase(phrase) => {
+                let postings: Option<Vec<&BTreeMap<String, Vec<usize>>>> =
+                    phrase.iter().map(|word| self.postings.get(word)).collect();
+                let postings = match postings {
+                    Some(v) => v,
+                    None => return (positions, words),
+                };
+                for (hash, starts) in postings[0] {
+                    let matches: Vec<usize> = starts
+                        .iter()
+                        .filter(|start| {
+                            postings[1..]
+                                .iter()
+                                .enumerate()
+                                .all(|(i, v)| match v.get(hash) {
+                                    Some(v) => v.binary_search(&(**start + i + 1)).is_ok(),
+                                    None => false,
+                                })
+                        })
+                        .cloned()
+                        .collect();
+                    if !matches.is_empty() {
+                        positions.insert(hash.to_owned(), matches);
+                    }
+                }
+                if !positions.is_empty() {
+                    words.extend(phrase.iter().cloned());
+                }
+            }
+        }
+        (positions, words)
+    }
+}
+
+/// Adds the index file to the .gitignore of the tracker directory, the index is derived from
+/// the issue files and would conflict on every merge
+fn ignore(tracker_dir: &Path) -> Result<(), String> {
+    let path = tracker_dir.join(".gitignore");
+    let mut data = match fs::read_to_string(&path) {
+        Ok(v) => v,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    let entry = format!("/{}", INDEX_FILE);
+    if data
+        .lines()
+        .any(|v| v.trim() == entry || v.trim() == INDEX_FILE)
+    {
+        return Ok(());
+    }
+    if !data.is_empty() && !data.ends_with('\n') {
+        data.push('\n');
+    }
+    data.push_str(&entry);
+    data.push('\n');
+    fs::write(&path, data).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn fingerprint(sections: &[String]) -> String {
+    format!("{:x}", Sha256::digest(sections.join("\0").as_bytes()))
+}
+
+/// Excerpt around the first hit in the sections of an issue split into plain and highlighted
+/// parts, hits in the description and comments are preferred over the title
+pub fn snippet(sections: &[String], words: &BTreeSet<String>) -> Vec<(String, bool)> {
+    let order = (1..sections.len()).chain(0..1);
+    for i in order {
+        let text = &sections[i];
+        let tokens = tokens(text);
+        let first = match tokens.iter().position(|(_, _, v)| words.contains(v)) {
+            Some(v) => v,
+            None => continue,
+        };
+
+        let from = first.saturating_sub(SNIPPET_BEFORE);
+        let to = (first + SNIPPET_AFTER).min(tokens.len() - 1);
+        let mut parts = Vec::new();
+        if from > 0 {
+            parts.push(("... ".to_string(), false));
+        }
+        let mut offset = tokens[from].0;
+        for (start, end, word) in &tokens[from..=to] {
+            if words.contains(word) {
+                parts.push((text[offset..*start].to_string(), false));
+                parts.push((text[*start..*end].to_string(), true));
+                offset = *end;
+            }
+        }
+        parts.push((text[offset..tokens[to].1].to_string(), false));
+        if to < tokens.len() - 1 {
+            parts.push((" ...".to_string(), false));
+        }
+
+        // keep snippets on a single line
+        return parts
+            .into_iter()
+            .map(|(v, highlight)| {
+                let mut line = String::new();
+                for c in v.chars() {
+                    if !c.is_whitespace() {
+                        line.push(c);
+                    } else if !line.ends_with(' ') {
+                        line.push(' ');
+                    }
+                }
+                (line, highlight)
+            })
+            .filter(|(v, _)| !v.is_empty())
+            .collect();
+    }
+    vec![]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index(issues: &[(&str, &[&str])]) -> Index {
+        let mut index = Index {
+            documents: BTreeMap::new(),
+            postings: BTreeMap::new(),
+        };
+        for (hash, sections) in issues {
+            let sections: Vec<String> = sections.iter().map(|v| v.to_string()).collect();
+            index.add(hash.to_string(), fingerprint(&sections), &sections);
+        }
+        index
+    }
+
+    fn search(index: &Index, query: &str) -> Vec<String> {
+        index
+            .search(&parse(query).unwrap())
+            .into_iter()
+            .map(|hit| hit.hash)
+            .collect()
+    }
+
+    fn word(v: &str) -> Term {
+        Term::Word(v.to_string())
+    }
+
+    #[test]
+    fn parse_terms() {
+        assert_eq!(
+            parse("Parser pars* \"Null  pointer\""),
+            Ok(vec![
+                word("parser"),
+                Term::Prefix("pars".to_string()),
+                Term::Phrase(vec!["null".to_string(), "pointer".to_string()]),
+            ])
+        );
+        assert_eq!(
+            parse("\"crash\" foo-bar"),
+            Ok(vec![word("crash"), word("foo"), word("bar")])
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("  * \"\" ").is_err());
+        assert!(parse("\"null pointer").is_err());
+    }
+
+    #[test]
+    fn tokens_lowercase_with_ranges() {
+        assert_eq!(
+            tokens("Crash, in UI!"),
+            vec![
+                (0, 5, "crash".to_string()),
+                (7, 9, "in".to_string()),
+                (10, 12, "ui".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn search_requires_all_terms() {
+        let index = index(&[
+            ("a", &["Parser crash", "null pointer in the parser"]),
+            ("b", &["Crash on start", "unrelated"]),
+            ("c", &["Parsing is slow", ""]),
+        ]);
+        assert_eq!(search(&index, "crash parser"), vec!["a"]);
+        assert!(search(&index, "crash missing").is_empty());
+        let mut hits = search(&index, "pars*");
+        hits.sort();
+        assert_eq!(hits, vec!["a", "c"]);
+    }
+
+    #[test]
+    fn search_phrases() {
+        let index = index(&[
+            ("a", &["Title", "null pointer dereference"]),
+            ("b", &["Title", "pointer to null"]),
+            // phrases don't match across sections
+            ("c", &["Title null", "pointer"]),
+        ]);
+        assert_eq!(search(&index, "\"null pointer\""), vec!["a"]);
+    }
+
+    #[test]
+    fn search_ranks_title_hits_first() {
+        let index = index(&[
+            ("a", &["Something else", "the crash happens here"]),
+            ("b", &["Crash on start", "happens here"]),
+        ]);
+        assert_eq!(search(&index, "crash"), vec!["b", "a"]);
+    }
+
+    #[test]
+    fn snippet_highlights_words() {
+        let sections = vec!["Title".to_string(), "A  null\npointer here".to_string()];
+        let words: BTreeSet<String> = vec!["null".to_string()].into_iter().collect();
+        assert_eq!(
+            snippet(&sections, &words),
+            vec![
+                ("A ".to_string(), false),
+                ("null".to_string(), true),
+                (" pointer here".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn index_file_is_ignored_once() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "*.tmp").unwrap();
+        ignore(dir.path()).unwrap();
+        ignore(dir.path()).unwrap();
+        let data = fs::read_to_string(dir.path().join(".gitignore")).unwrap();
+        assert_eq!(data, "*.tmp\n/search_index.json\n");
+    }
+}
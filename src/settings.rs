@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::env;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use chrono::format::{Item, StrftimeItems};
+
+use super::board;
+use super::kinds::Kinds;
+use super::Config;
+
+/// Keys of the layered settings with their description
+pub const KEYS: &[(&str, &str)] = &[
+    (
+        "user",
+        "Name recorded as author of issues, comments and changes",
+    ),
+    ("default_type", "Type of newly opened issues"),
+    ("default_priority", "Priority of newly opened issues"),
+    ("default_assignee", "Assignee of newly opened issues"),
+    ("date_format", "strftime format of displayed dates"),
+    (
+        "editor",
+        "Editor for descriptions and comments, before $VISUAL and $EDITOR",
+    ),
+    (
+        "board_columns",
+        "Statuses shown as board columns in order, e.g. Open, InProgress, Review",
+    ),
+    (
+        "board_wip_limits",
+        "Work in progress limits of board columns, e.g. InProgress=3, Review=2",
+    ),
+];
+
+pub const DEFAULT_DATE_FORMAT: &str = "%Y-%m-%d %H:%M";
+
+/// Prefix of environment variables overriding settings, e.g. COBWEB_USER
+const ENV_PREFIX: &str = "COBWEB_";
+
+/// Layers of the settings, later layers override earlier ones
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Layer {
+    Global,
+    Repository,
+    Environment,
+}
+
+impl fmt::Display for Layer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match *self {
+            Layer::Global => "global",
+            Layer::Repository => "repository",
+            Layer::Environment => "environment",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub struct Settings {
+    global: BTreeMap<String, String>,
+    repository: BTreeMap<String, String>,
+    environment: BTreeMap<String, String>,
+    date_format: String,
+}
+
+impl Settings {
+    /// Reads the global settings file, the repository settings of the tracker config
+    /// and the environment
+    pub fn load(config: &Config) -> Result<Settings, String> {
+        let global = match global_path() {
+            Some(path) => read(&path)?,
+            None => BTreeMap::new(),
+        };
+
+        let mut repository = config.settings().to_owned();
+        if let Some(ref v) = *config.user() {
+            repository
+                .entry("user".to_string())
+                .or_insert_with(|| v.to_owned());
+        }
+
+        let mut environment = BTreeMap::new();
+        for (key, _) in KEYS {
+            if let Ok(v) = env::var(format!("{}{}", ENV_PREFIX, key.to_uppercase())) {
+                environment.insert(key.to_string(), v);
+            }
+        }
+
+        let mut settings = Settings {
+            global,
+            repository,
+            environment,
+            date_format: DEFAULT_DATE_FORMAT.to_string(),
+        };
+        // only config set validates, the environment and the files may hold anything
+        if let Some(v) = settings.value("date_format") {
+            if date_format(v).is_ok() {
+                settings.date_format = v.to_string();
+            }
+        }
+        Ok(settings)
+    }
+
+    /// Returns the effective value of key and the layer it comes from
+    pub fn get(&self, key: &str) -> Option<(&str, Layer)> {
+        [
+            (&self.environment, Layer::Environment),
+            (&self.repository, Layer::Repository),
+            (&self.global, Layer::Global),
+        ]
+        .iter()
+        .find_map(|(values, layer)| values.get(key).map(|v| (v.as_str(), *layer)))
+    }
+
+    pub fn value(&self, key: &str) -> Option<&str> {
+        self.get(key).map(|(v, _)| v)
+    }
+
+    pub fn layer(&self, layer: Layer) -> &BTreeMap<String, String> {
+        match layer {
+            Layer::Global => &self.global,
+            Layer::Repository => &self.repository,
+            Layer::Environment => &self.environment,
+        }
+    }
+
+    /// Returns the configured date format, invalid formats fall back to the default
+    pub fn date_format(&self) -> &str {
+        &self.date_format
+    }
+}
+
+/// Path of the global settings file, $XDG_CONFIG_HOME/cobweb/config or ~/.config/cobweb/config
+pub fn global_path() -> Option<PathBuf> {
+    let dir = match env::var_os("XDG_CONFIG_HOME") {
+        Some(v) if !v.is_empty() => PathBuf::from(v),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+    Some(dir.join("cobweb").join("config"))
+}
+
+fn read(path: &PathBuf) -> Result<BTreeMap<String, String>, String> {
+    match fs::read_to_string(path) {
+        Ok(v) => parse(&v).map_err(|e| format!("{}: {}", path.display(), e)),
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => Ok(BTreeMap::new()),
+        Err(e) => Err(format!("{}: {}", path.display(), e)),
+    }
+}
+
+/// Writes the global settings file, creating its directory when missing
+pub fn write_global(values: &BTreeMap<String, String>) -> Result<(), String> {
+    let path = match global_path() {
+        Some(v) => v,
+        None => return Err("neither $XDG_CONFIG_HOME nor $HOME is set".to_string()),
+    };
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+    }
+    fs::write(&path, render(values)).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+/// Parses 'key = value' lines, empty lines and lines starting with # are ignored
+pub fn parse(text: &str) -> Result<BTreeMap<String, String>, String> {
+    let mut values = BTreeMap::new();
+    for (i, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.splitn(2, '=');
+        let key = parts.next().unwrap_or_default().trim();
+        match parts.next() {
+            Some(v) => values.insert(key.to_string(), v.trim().to_string()),
+            None => return Err(format!("line {}: expected key = value", i + 1)),
+        };
+    }
+    Ok(values)
+}
+
+pub fn render(values: &BTreeMap<String, String>) -> String {
+    values
+        .iter()
+        .map(|(key, value)| format!("{} = {}\n", key, value))
+        .collect()
+}
+
+/// Validates value for key and returns its normalised form
+pub fn validate(key: &str, value: &str, kinds: &Kinds) -> Result<String, String> {
+    let value = value.trim();
+    if !KEYS.iter().any(|(v, _)| *v == key) {
+        let keys: Vec<&str> = KEYS.iter().map(|(v, _)| *v).collect();
+        return Err(format!(
+            "unknown key '{}', possible keys: {}",
+            key,
+            keys.join(", ")
+        ));
+    }
+    if value.is_empty() {
+        return Err(format!("empty value for '{}'", key));
+    }
+
+    match key {
+        "default_type" => kinds.type_parser(value).map(|v| v.to_string()),
+        "default_priority" => kinds.priority_parser(value).map(|v| v.to_string()),
+        "date_format" => date_format(value).map(|_| value.to_string()),
+        "board_columns" => board::columns(value).map(|_| value.to_string()),
+        "board_wip_limits" => board::wip_limits(value).map(|_| value.to_string()),
+        _ => Ok(value.to_string()),
+    }
+}
+
+/// Checks that value is a strftime format chrono can render
+fn date_format(value: &str) -> Result<(), String> {
+    if StrftimeItems::new(value).any(|item| item == Item::Error) {
+        Err(format!("invalid date format '{}'", value))
+    } else {
+        Ok(())
+    }
+}
@@ -0,0 +1,151 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Datelike, Duration, Local, TimeZone};
+
+use super::{Issue, IssueStatus};
+
+/// Weeks of throughput reported by default
+pub const DEFAULT_WEEKS: usize = 12;
+
+/// Most weeks of throughput reported, 20 years
+pub const MAX_WEEKS: usize = 1040;
+
+/// Distribution of durations in seconds
+pub struct Durations {
+    pub count: usize,
+    pub mean: i64,
+    pub median: i64,
+    pub p85: i64,
+}
+
+/// Breakdowns and flow metrics of a set of issues
+pub struct Stats {
+    pub total: usize,
+    /// counts by status, type, priority, assignee and author, largest first
+    pub groups: Vec<(&'static str, Vec<(String, usize)>)>,
+    /// creation to close
+    pub lead_time: Option<Durations>,
+    /// start to close
+    pub cycle_time: Option<Durations>,
+    /// closed issues per ISO week, oldest first
+    pub throughput: Vec<(String, usize)>,
+    /// closed issues without a close in their history, e.g. closed before the history was
+    /// recorded, they are left out of lead time, cycle time and throughput
+    pub undated: usize,
+}
+
+pub fn compute(issues: &[&Issue], weeks: usize, now: DateTime<Local>) -> Stats {
+    let group = |key: &dyn Fn(&Issue) -> String| {
+        let mut counts: BTreeMap<String, usize> = BTreeMap::new();
+        for issue in issues {
+            *counts.entry(key(issue)).or_default() += 1;
+        }
+        let mut counts: Vec<(String, usize)> = counts.into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    };
+    let groups = vec![
+        ("status", group(&|issue| issue.status().to_string())),
+        ("type", group(&|issue| issue.typ().to_string())),
+        ("priority", group(&|issue| issue.priority().to_string())),
+        (
+            "assignee",
+            group(&|issue| {
+                issue
+                    .assigned_to()
+                    .to_owned()
+                    .unwrap_or_else(|| "unassigned".to_string())
+            }),
+        ),
+        ("author", group(&|issue| issue.author().to_string())),
+    ];
+
+    let closed: Vec<(&Issue, i64)> = issues
+        .iter()
+        .filter_map(|issue| closed_at(issue).map(|ts| (*issue, ts)))
+        .collect();
+    let undated = issues
+        .iter()
+        .filter(|issue| *issue.status() == IssueStatus::Closed)
+        .count()
+        - closed.len();
+    let lead_times = closed
+        .iter()
+        .map(|(issue, ts)| ts - issue.creation_date())
+        .filter(|v| *v >= 0)
+        .collect();
+    let cycle_times = closed
+        .iter()
+        .map(|(issue, ts)| ts - issue.start_date())
+        .filter(|v| *v >= 0)
+        .collect();
+
+    // weeks without closed issues are reported with 0
+    let mut throughput: Vec<(String, usize)> = (0..weeks)
+        .rev()
+        .map(|i| (week(now - Duration::weeks(i as i64)), 0))
+        .collect();
+    for (_, ts) in &closed {
+        let week = week(Local.timestamp(*ts, 0));
+        if let Some(v) = throughput.iter_mut().find(|(v, _)| *v == week) {
+            v.1 += 1;
+        }
+    }
+
+    Stats {
+        total: issues.len(),
+        groups,
+        lead_time: durations(lead_times),
+        cycle_time: durations(cycle_times),
+        throughput,
+        undated,
+    }
+}
+
+/// Returns when a closed issue was last closed according to its history
+pub fn closed_at(issue: &Issue) -> Option<i64> {
+    if *issue.status() != IssueStatus::Closed {
+        return None;
+    }
+    let closed = IssueStatus::Closed.to_string();
+    issue
+        .history()
+        .iter()
+        .filter(|change| change.field() == "status")
+        .filter(|change| change.new_value().as_deref() == Some(closed.as_str()))
+        .map(|change| change.creation_date())
+        .max()
+}
+
+fn durations(mut values: Vec<i64>) -> Option<Durations> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_unstable();
+    let count = values.len();
+    // nearest rank percentile
+    let percentile = |p: usize| values[((count * p).div_ceil(100)).max(1) - 1];
+    Some(Durations {
+        count,
+        mean: values.iter().sum::<i64>() / count as i64,
+        median: percentile(50),
+        p85: percentile(85),
+    })
+}
+
+/// ISO week of a date, e.g. '2026-W42'
+fn week(date: DateTime<Local>) -> String {
+    let week = date.iso_week();
+    format!("{}-W{:02}", week.year(), week.week())
+}
+
+/// Formats seconds as days, hours and minutes, e.g. '2d 4h', days count 24 hours
+pub fn format_duration(seconds: i64) -> String {
+    let minutes = seconds / 60;
+    let (days, hours, minutes) = (minutes / (24 * 60), minutes / 60 % 24, minutes % 60);
+    match (days, hours, minutes) {
+        (0, 0, m) => format!("{}m", m),
+        (0, h, m) => format!("{}h {}m", h, m),
+        (d, h, _) => format!("{}d {}h", d, h),
+    }
+}
@@ -1,4 +1,7 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet};
 use std::env;
+use std::fs;
 use std::io::{stdin, stdout, Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
 use std::process;
@@ -7,10 +10,30 @@ use std::str::FromStr;
 use chrono::{Local, TimeZone};
 use clap::ArgMatches;
 
+use super::arguments::Status;
+use super::attachments;
+use super::board;
+use super::commits;
+use super::custom;
+use super::dates;
 use super::display;
+use super::fields::Field;
+use super::hashes;
+use super::kinds::Kinds;
+use super::links;
+use super::milestones::{self, Summary};
+use super::output::{self, Format};
+use super::query::Query;
+use super::search::{self, Index};
+use super::settings::{self, Layer, Settings};
+use super::stats;
+use super::tui::{self, Action, Prompt, Terminal, View};
+use super::workflow;
+use super::worklog::{self, TimeTotals, Timer};
 use super::{
-    Issue, IssueFilter, IssueHash, IssuePriority, IssueStatus, IssueStorage, IssueType,
-    IssuesHandler,
+    Issue, IssueAttachment, IssueChange, IssueComment, IssueFilter, IssueHash, IssueLink,
+    IssueLinkKind, IssuePriority, IssueStatus, IssueStorage, IssueType, IssueWorkLog,
+    IssuesHandler, Milestone,
 };
 
 pub fn init(_args: &ArgMatches, working_dir: &PathBuf) {
@@ -24,24 +47,188 @@ pub fn init(_args: &ArgMatches, working_dir: &PathBuf) {
 }
 
 pub fn list(args: &ArgMatches, working_dir: &PathBuf) {
-    let (_, handler) = load_issues(working_dir);
+    let (storage, handler) = load_issues(working_dir);
+
+    let kinds = Kinds::new(storage.config());
+    let settings = settings_parser(&storage);
+    let mut issues = issues_filtered_parser(args, &storage, &handler);
+
+    // sort
+    if let Some(v) = args.value_of("sort") {
+        let keys = sort_parser(v);
+        issues.sort_by(|a, b| {
+            keys.iter()
+                .map(|(field, descending)| field.compare(a, b, *descending, &kinds))
+                .find(|ord| *ord != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        });
+    }
+
+    let format = format_parser(args);
+    if format != Format::Text {
+        output::issues(&issues, format);
+        return;
+    }
+
+    if issues.len() == 1 {
+        println!("Found 1 issue");
+    } else {
+        println!("Found {} issues", issues.len());
+    }
+    if args.is_present("table") {
+        let columns = columns_parser(args);
+        display::issue_table(&issues, &columns, &kinds, settings.date_format());
+    } else {
+        for issue in issues {
+            let relations = links::relations(&handler, issue);
+            display::issue_long(issue, &relations, &kinds, settings.date_format());
+        }
+    }
+}
+
+pub fn tree(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let depth = args.value_of("depth").map(|v| match v.parse::<usize>() {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing given depth as integer: {}, {}", v, e);
+            process::exit(-1);
+        }
+    });
+
+    let issues: BTreeMap<String, &Issue> = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .map(|(_, issue)| (issue.hash().to_string(), issue))
+        .collect();
+    let matched: BTreeSet<String> = issues_filtered_parser(args, &storage, &handler)
+        .iter()
+        .map(|issue| issue.hash().to_string())
+        .collect();
+
+    // issues are shown if they match or have a matching descendant
+    let mut visible = BTreeSet::new();
+    for hash in &matched {
+        let mut next = Some(hash.to_owned());
+        while let Some(hash) = next {
+            if !visible.insert(hash.clone()) {
+                break;
+            }
+            next = issues
+                .get(&hash)
+                .and_then(|issue| issue.parent().as_ref().map(|v| v.to_string()));
+        }
+    }
+
+    let mut children: BTreeMap<String, Vec<&Issue>> = BTreeMap::new();
+    for issue in issues.values() {
+        if let Some(ref parent) = *issue.parent() {
+            children.entry(parent.to_string()).or_default().push(issue);
+        }
+    }
+    for v in children.values_mut() {
+        v.sort_by_key(|issue| issue.creation_date());
+    }
 
-    // Filter
+    let mut roots: Vec<&Issue> = match args.value_of("root") {
+        Some(v) => {
+            let hash = match hashes::resolve(&handler, v) {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("Error resolving issue hash: {}", e);
+                    process::exit(-1);
+                }
+            };
+            match handler.issue(&hash) {
+                Some(v) => vec![v],
+                None => {
+                    eprintln!("Issue with hash {} doesn't exist", hash);
+                    process::exit(-1);
+                }
+            }
+        }
+        None => issues
+            .values()
+            .filter(|issue| match *issue.parent() {
+                Some(ref v) => !issues.contains_key(&v.to_string()),
+                None => true,
+            })
+            .filter(|issue| visible.contains(&issue.hash().to_string()))
+            .cloned()
+            .collect(),
+    };
+    roots.sort_by_key(|issue| issue.creation_date());
+
+    let kinds = Kinds::new(storage.config());
+    display::issue_tree(&roots, &children, &visible, &matched, depth, &kinds);
+}
+
+fn issues_filtered_parser<'a>(
+    args: &ArgMatches,
+    storage: &IssueStorage,
+    handler: &'a IssuesHandler,
+) -> Vec<&'a Issue> {
+    let filter = issue_filter_parser(args, storage, handler);
+    let issues = handler.issues_filtered(&filter);
+
+    // query
+    let kinds = Kinds::new(storage.config());
+    let matches = args
+        .value_of("query")
+        .map(|v| match Query::parse(v, handler, &kinds) {
+            Ok(query) => query.evaluate(handler),
+            Err(e) => {
+                eprintln!("Error parsing query: {}, {}", v, e);
+                process::exit(-1);
+            }
+        });
+
+    // blocked by open issues
+    let blocked = links::blocked(handler);
+    let blocking = if args.is_present("blocked") {
+        Some(true)
+    } else if args.is_present("unblocked") {
+        Some(false)
+    } else {
+        None
+    };
+
+    issues
+        .into_iter()
+        .map(|(_, issue)| issue)
+        .filter(|issue| match matches {
+            Some(ref v) => v.contains(&issue.hash().to_string()),
+            None => true,
+        })
+        .filter(|issue| match blocking {
+            Some(v) => blocked.contains(&issue.hash().to_string()) == v,
+            None => true,
+        })
+        .collect()
+}
+
+fn issue_filter_parser(
+    args: &ArgMatches,
+    storage: &IssueStorage,
+    handler: &IssuesHandler,
+) -> IssueFilter {
+    let kinds = Kinds::new(storage.config());
     let mut filter = IssueFilter::new();
     // hash
-    if let Some(v) = hash_parser(args) {
+    if let Some(v) = hash_parser(handler, args) {
         filter.set_hash_match(v);
     };
     // type
-    if let Some(v) = type_parser(args) {
+    if let Some(v) = type_parser(args, &kinds) {
         filter.set_type_match(v);
     };
     // parent
     if let Some(v) = args.value_of("parent") {
-        let hash = match IssueHash::from_str(v) {
+        let hash = match hashes::resolve(handler, v) {
             Ok(v) => v,
             Err(e) => {
-                eprintln!("Error parsing issue parent hash: {}, {}", v, e);
+                eprintln!("Error resolving issue parent hash: {}", e);
                 process::exit(-1);
             }
         };
@@ -61,7 +248,7 @@ pub fn list(args: &ArgMatches, working_dir: &PathBuf) {
     }
     // to creation date
     if let Some(v) = args.value_of("to_creation") {
-        let ts = datetime_parser(v);
+        let (_, ts) = datetime_range_parser(v);
         filter.set_creation_date_to_match(ts);
     }
     // title
@@ -80,7 +267,7 @@ pub fn list(args: &ArgMatches, working_dir: &PathBuf) {
         }
     }
     // priority
-    if let Some(v) = priority_parser(args) {
+    if let Some(v) = priority_parser(args, &kinds) {
         filter.set_priority_match(v);
     };
     // status
@@ -101,7 +288,7 @@ pub fn list(args: &ArgMatches, working_dir: &PathBuf) {
     }
     // to start date
     if let Some(v) = args.value_of("to_start") {
-        let ts = datetime_parser(v);
+        let (_, ts) = datetime_range_parser(v);
         filter.set_start_date_to_match(ts);
     }
     // from due date
@@ -111,7 +298,7 @@ pub fn list(args: &ArgMatches, working_dir: &PathBuf) {
     }
     // to due date
     if let Some(v) = args.value_of("to_due") {
-        let ts = datetime_parser(v);
+        let (_, ts) = datetime_range_parser(v);
         filter.set_due_date_to_match(ts);
     }
     // from progress
@@ -142,36 +329,38 @@ pub fn list(args: &ArgMatches, working_dir: &PathBuf) {
             process::exit(-1);
         }
     };
-
-    let issues = handler.issues_filtered(&filter);
-
-    if issues.len() == 1 {
-        println!("Found 1 issue");
-    } else {
-        println!("Found {} issues", issues.len());
+    // labels
+    if let Some(values) = args.values_of("tag") {
+        for v in values {
+            filter.add_label_match(label_parser(v));
+        }
+    }
+    // milestone
+    if let Some(v) = args.value_of("milestone") {
+        let milestones = milestones_parser(storage);
+        filter.set_milestone_match(milestone_name_parser(&milestones, v));
     }
-    for (_, issue) in issues {
-        display::issue_long(issue);
+    // custom fields
+    if let Some(values) = args.values_of("field") {
+        for v in values {
+            match custom_field_parser(storage, v) {
+                (key, Some(value)) => filter.add_custom_field_match(key, value),
+                (key, None) => {
+                    eprintln!("Error missing value for field filter: {}", key);
+                    process::exit(-1);
+                }
+            }
+        }
     }
+
+    filter
 }
 
 pub fn open(args: &ArgMatches, working_dir: &PathBuf) {
     let (storage, mut handler) = load_issues(working_dir);
 
     // set issue author
-    let author = if let Some(v) = args.value_of("author") {
-        v.to_owned()
-    } else if let Some(ref v) = *storage.config().user() {
-        v.to_owned()
-    } else {
-        match user::get_user_name() {
-            Ok(user) => user,
-            Err(e) => {
-                println!("Error retrieving user: {}", e);
-                process::exit(-1);
-            }
-        }
-    };
+    let author = author_parser(args, &storage);
 
     // set issue title
     let title = match args.value_of("title") {
@@ -183,26 +372,44 @@ pub fn open(args: &ArgMatches, working_dir: &PathBuf) {
     };
     let mut issue = Issue::new(&author, title);
 
+    // set configured defaults
+    let kinds = Kinds::new(storage.config());
+    if let Err(e) = issue_defaults_setter(&mut issue, &kinds, &settings_parser(&storage)) {
+        eprintln!("Error parsing configured defaults: {}", e);
+        process::exit(-1);
+    }
+
     // set issue type
-    issue_type_parser(&mut issue, args);
+    issue_type_parser(&mut issue, args, &kinds);
     // set issue parent
-    issue_parent_parser(&mut issue, args, &storage);
+    issue_parent_parser(&mut issue, args, &handler);
     // set issue priority
-    issue_priority_parser(&mut issue, args);
+    issue_priority_parser(&mut issue, args, &kinds);
     // set issue status
     issue_status_parser(&mut issue, args);
     // set assigned to
     issue_assigned_to_parser(&mut issue, args);
+    // set milestone
+    issue_milestone_parser(&mut issue, args, &storage);
     // set issue start date
     issue_start_date_parser(&mut issue, args);
     // set issue due date, date format
     issue_due_date_parser(&mut issue, args);
     // set issue progress
     issue_progress_parser(&mut issue, args);
+    // set issue progress roll-up
+    issue_rollup_parser(&mut issue, args);
+    // set issue estimate
+    issue_estimate_parser(&mut issue, args);
+    // set issue labels
+    issue_labels_parser(&mut issue, args);
+    // set issue custom fields
+    issue_custom_fields_parser(&mut issue, args, &storage);
     // set issue description
-    issue_description_edit_parser(&mut issue, args);
+    issue_description_edit_parser(&mut issue, args, &storage);
 
     handler.insert_issue(issue);
+    progress_rollup(&mut handler, &storage, &user_parser(&storage));
     store_issues(&handler, &storage);
 }
 
@@ -210,6 +417,19 @@ pub fn edit(args: &ArgMatches, working_dir: &PathBuf) {
     let (storage, mut handler) = load_issues(working_dir);
 
     let mut issue = issue_hash_parser(&handler, args);
+    let original = issue.clone();
+
+    // progress of issues with children is derived when roll-up is enabled
+    if args.is_present("progress")
+        && (issue.progress_rollup() || storage.config().progress_rollup())
+        && handler.find_dependend_issues(issue.hash()).len() > 1
+    {
+        eprintln!(
+            "Error progress of issue {} is rolled up from its children",
+            issue.hash()
+        );
+        process::exit(-1);
+    }
 
     // set issue author
     if let Some(v) = args.value_of("author") {
@@ -222,25 +442,53 @@ pub fn edit(args: &ArgMatches, working_dir: &PathBuf) {
     }
 
     // set issue type
-    issue_type_parser(&mut issue, args);
+    let kinds = Kinds::new(storage.config());
+    issue_type_parser(&mut issue, args, &kinds);
     // set issue parent
-    issue_parent_parser(&mut issue, args, &storage);
+    issue_parent_parser(&mut issue, args, &handler);
     // set issue priority
-    issue_priority_parser(&mut issue, args);
+    issue_priority_parser(&mut issue, args, &kinds);
     // set issue status
     issue_status_parser(&mut issue, args);
     // set assigned to
     issue_assigned_to_parser(&mut issue, args);
+    // set milestone
+    issue_milestone_parser(&mut issue, args, &storage);
     // set issue start date
     issue_start_date_parser(&mut issue, args);
     // set issue due date, date format
     issue_due_date_parser(&mut issue, args);
     // set issue progress
     issue_progress_parser(&mut issue, args);
+    // set issue progress roll-up
+    issue_rollup_parser(&mut issue, args);
+    // set issue estimate
+    issue_estimate_parser(&mut issue, args);
+    // set issue labels
+    issue_labels_parser(&mut issue, args);
+    // set issue custom fields
+    issue_custom_fields_parser(&mut issue, args, &storage);
+
+    // check status change against the workflow, before an editor is opened so an illegal
+    // change doesn't discard the typed description, a required description is checked after
+    let pending: &[&str] = if args.is_present("description_edit") {
+        &["description"]
+    } else {
+        &[]
+    };
+    issue_transition_checker(&storage, &original, &issue, "edit", pending);
+
     // set issue description
-    issue_description_edit_parser(&mut issue, args);
+    issue_description_edit_parser(&mut issue, args, &storage);
+    if !pending.is_empty() {
+        issue_transition_checker(&storage, &original, &issue, "edit", &[]);
+    }
+
+    // record changes
+    issue_changes_recorder(&original, &mut issue, &user_parser(&storage));
 
     handler.insert_issue(issue);
+    progress_rollup(&mut handler, &storage, &user_parser(&storage));
     store_issues(&handler, &storage);
 }
 
@@ -248,18 +496,50 @@ pub fn close(args: &ArgMatches, working_dir: &PathBuf) {
     let (storage, mut handler) = load_issues(working_dir);
 
     let mut issue = issue_hash_parser(&handler, args);
+    let original = issue.clone();
 
-    issue.set_status(IssueStatus::Closed);
-    let _ = issue.set_progress(100);
+    issue_closer(&mut issue);
+
+    // check status change against the workflow
+    issue_transition_checker(&storage, &original, &issue, "close", &[]);
+
+    // record changes
+    issue_changes_recorder(&original, &mut issue, &user_parser(&storage));
+
+    handler.insert_issue(issue);
+    progress_rollup(&mut handler, &storage, &user_parser(&storage));
+    store_issues(&handler, &storage);
+}
+
+pub fn reopen(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let mut issue = issue_hash_parser(&handler, args);
+    let original = issue.clone();
+
+    match *issue.status() {
+        IssueStatus::Closed | IssueStatus::Rejected => issue.set_status(IssueStatus::Open),
+        ref status => {
+            eprintln!("Error issue {} is {}, not closed", issue.hash(), status);
+            process::exit(-1);
+        }
+    }
+
+    // check status change against the workflow
+    issue_transition_checker(&storage, &original, &issue, "reopen", &[]);
+
+    // record changes
+    issue_changes_recorder(&original, &mut issue, &user_parser(&storage));
 
     handler.insert_issue(issue);
+    progress_rollup(&mut handler, &storage, &user_parser(&storage));
     store_issues(&handler, &storage);
 }
 
 pub fn remove(args: &ArgMatches, working_dir: &PathBuf) {
     let (storage, handler) = load_issues(working_dir);
 
-    let hash = hash_parser(args).unwrap();
+    let hash = hash_parser(&handler, args).unwrap();
 
     let dependencies = handler.find_dependend_issues(&hash);
 
@@ -270,7 +550,7 @@ pub fn remove(args: &ArgMatches, working_dir: &PathBuf) {
     }
     for hash in &dependencies {
         let issue = handler.issue(hash).unwrap();
-        display::issue_short(issue);
+        display::issue_short(issue, &hashes::abbreviation(&handler, hash));
     }
 
     loop {
@@ -301,143 +581,1838 @@ pub fn remove(args: &ArgMatches, working_dir: &PathBuf) {
             }
         }
     }
+
+    // update progress of remaining ancestors and drop links to the removed issues
+    let (storage, mut handler) = load_issues(working_dir);
+    links_cleaner(&mut handler, &dependencies, &user_parser(&storage));
+    progress_rollup(&mut handler, &storage, &user_parser(&storage));
+    store_issues(&handler, &storage);
+    attachments_cleaner(&handler, &storage);
+}
+
+pub fn comment(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let mut issue = issue_hash_parser(&handler, args);
+
+    // set comment author
+    let author = author_parser(args, &storage);
+
+    // open editor for comment text
+    let text = editor_parser(&None, "comment", &storage);
+    if text.is_empty() {
+        eprintln!("Empty comment, aborted");
+        process::exit(-1);
+    }
+
+    issue.add_comment(IssueComment::new(&author, &text));
+
+    handler.insert_issue(issue);
+    store_issues(&handler, &storage);
+}
+
+pub fn history(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let issue = issue_hash_parser(&handler, args);
+
+    let settings = settings_parser(&storage);
+    display::history(
+        &issue,
+        &hashes::abbreviation(&handler, issue.hash()),
+        settings.date_format(),
+    );
+}
+
+pub fn link(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let (kind, mut issue, target) = link_parser(&handler, args);
+    if links::creates_cycle(&handler, kind, issue.hash(), &target) {
+        eprintln!(
+            "Error linking {} {} {}, the link would create a cycle",
+            issue.hash(),
+            links::name(kind),
+            target
+        );
+        process::exit(-1);
+    }
+    let linked = issue
+        .links()
+        .iter()
+        .any(|v| v.kind() == kind && v.target().to_string() == target.to_string());
+    if linked {
+        eprintln!(
+            "Issue {} already {} {}",
+            issue.hash(),
+            links::name(kind),
+            target
+        );
+        process::exit(-1);
+    }
+
+    issue.add_link(IssueLink::new(kind, target.clone()));
+    let change = format!("{} {}", links::name(kind), target);
+    issue.add_change(IssueChange::new(
+        "links",
+        None,
+        Some(change),
+        &user_parser(&storage),
+    ));
+
+    handler.insert_issue(issue);
+    store_issues(&handler, &storage);
+}
+
+pub fn unlink(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let (kind, mut issue, target) = link_parser(&handler, args);
+    if !issue.remove_link(kind, &target) {
+        eprintln!(
+            "Issue {} doesn't have a link {} {}",
+            issue.hash(),
+            links::name(kind),
+            target
+        );
+        process::exit(-1);
+    }
+    let change = format!("{} {}", links::name(kind), target);
+    issue.add_change(IssueChange::new(
+        "links",
+        Some(change),
+        None,
+        &user_parser(&storage),
+    ));
+
+    handler.insert_issue(issue);
+    store_issues(&handler, &storage);
 }
 
-pub fn config(_args: &ArgMatches, working_dir: &PathBuf) {
-    let storage = match IssueStorage::find_from_path(working_dir) {
+pub fn attach(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let mut issue = issue_hash_parser(&handler, args);
+
+    let file = PathBuf::from(args.value_of("file").unwrap());
+    let name = match args.value_of("name") {
+        Some(v) => v.trim().to_string(),
+        None => file
+            .file_name()
+            .map(|v| v.to_string_lossy().to_string())
+            .unwrap_or_default(),
+    };
+    if name.is_empty() {
+        eprintln!("Error attachment name must not be empty");
+        process::exit(-1);
+    }
+    if issue.attachments().iter().any(|v| *v.name() == name) {
+        eprintln!(
+            "Issue {} already has an attachment named {}",
+            issue.hash(),
+            name
+        );
+        process::exit(-1);
+    }
+
+    let (digest, size) = match attachments::store(storage.path(), &file) {
         Ok(v) => v,
         Err(e) => {
-            eprintln!("Failed finding issue tracker meta files: {}", e);
+            eprintln!("Error storing attachment: {}", e);
             process::exit(-1);
         }
     };
+    issue.add_attachment(IssueAttachment::new(&name, &digest, size));
+    issue.add_change(IssueChange::new(
+        "attachments",
+        None,
+        Some(name),
+        &user_parser(&storage),
+    ));
+
+    handler.insert_issue(issue);
+    store_issues(&handler, &storage);
+}
+
+pub fn detach(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let mut issue = issue_hash_parser(&handler, args);
+
+    let name = args.value_of("name").unwrap();
+    if issue.remove_attachment(name).is_none() {
+        eprintln!(
+            "Issue {} doesn't have an attachment named {}",
+            issue.hash(),
+            name
+        );
+        process::exit(-1);
+    }
+    issue.add_change(IssueChange::new(
+        "attachments",
+        Some(name.to_string()),
+        None,
+        &user_parser(&storage),
+    ));
 
-    display::config(storage.config());
+    handler.insert_issue(issue);
+    store_issues(&handler, &storage);
+    attachments_cleaner(&handler, &storage);
 }
 
-fn load_issues(working_dir: &PathBuf) -> (IssueStorage, IssuesHandler) {
-    let storage = match IssueStorage::find_from_path(working_dir) {
+pub fn search(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let query: Vec<&str> = args.values_of("terms").unwrap().collect();
+    let query = query.join(" ");
+    let terms = match search::parse(&query) {
         Ok(v) => v,
         Err(e) => {
-            eprintln!("Failed finding issue tracker meta files: {}", e);
+            eprintln!("Error parsing search query: {}, {}", query, e);
+            process::exit(-1);
+        }
+    };
+    let limit = match args.value_of("limit").unwrap_or("10").parse::<usize>() {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing given limit as integer: {}", e);
             process::exit(-1);
         }
     };
 
-    let mut handler = IssuesHandler::new();
-    match handler.read_issues(&storage) {
-        Ok(_) => {}
+    // issues changed outside of cobweb, e.g. by a pull, are reindexed first
+    let index = match search_index_updater(&handler, &storage) {
+        Ok(v) => v,
         Err(e) => {
-            eprintln!("Reading issues failed due to: {}", e);
+            eprintln!("Error updating search index: {}", e);
             process::exit(-1);
         }
-    }
+    };
+    let hits = index.search(&terms);
 
-    (storage, handler)
+    if hits.len() == 1 {
+        println!("Found 1 issue");
+    } else {
+        println!("Found {} issues", hits.len());
+    }
+    for hit in hits.iter().take(limit) {
+        let hash = match IssueHash::from_str(&hit.hash) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        if let Some(issue) = handler.issue(&hash) {
+            let snippet = search::snippet(&search::sections(issue), &hit.words);
+            display::search_hit(
+                issue,
+                &hashes::abbreviation(&handler, &hash),
+                hit.score,
+                &snippet,
+            );
+        }
+    }
 }
 
-fn store_issues(handler: &IssuesHandler, storage: &IssueStorage) {
-    match handler.write_issues(&storage) {
-        Ok(_) => {}
-        Err(e) => {
-            eprintln!("Error writing issues: {}", e);
+pub fn stats(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let weeks = match args.value_of("weeks").map(|v| v.parse::<usize>()) {
+        None => stats::DEFAULT_WEEKS,
+        Some(Ok(v)) if v <= stats::MAX_WEEKS => v,
+        Some(Ok(v)) => {
+            eprintln!(
+                "Error given weeks {} exceed maximum of {}",
+                v,
+                stats::MAX_WEEKS
+            );
+            process::exit(-1);
+        }
+        Some(Err(e)) => {
+            eprintln!("Error parsing given weeks as integer: {}", e);
             process::exit(-1);
         }
+    };
+
+    let issues = issues_filtered_parser(args, &storage, &handler);
+    let stats = stats::compute(&issues, weeks, Local::now());
+
+    let format = format_parser(args);
+    if format != Format::Text {
+        output::stats(&stats, format);
+        return;
     }
+    display::stats(&stats);
 }
 
-fn hash_parser(args: &ArgMatches) -> Option<IssueHash> {
-    args.value_of("hash").map(|v| match IssueHash::from_str(v) {
+pub fn board(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let kinds = Kinds::new(storage.config());
+    let settings = settings_parser(&storage);
+    let statuses = settings
+        .value("board_columns")
+        .unwrap_or(board::DEFAULT_COLUMNS);
+    let statuses = match board::columns(statuses) {
         Ok(v) => v,
         Err(e) => {
-            eprintln!("Error parsing issue hash: {}, {}", v, e);
+            eprintln!("Error parsing configured board columns: {}", e);
             process::exit(-1);
         }
-    })
-}
-
-fn type_parser(args: &ArgMatches) -> Option<IssueType> {
-    // TODO: implement handling multiple types as argument
-    args.value_of("type").map(|v| match IssueType::from_str(v) {
+    };
+    let limits = match board::wip_limits(settings.value("board_wip_limits").unwrap_or("")) {
         Ok(v) => v,
         Err(e) => {
-            eprintln!("Error parsing issue type: {}, {}", v, e);
+            eprintln!("Error parsing configured board limits: {}", e);
             process::exit(-1);
         }
-    })
+    };
+
+    // highest priority first, then oldest
+    let mut issues = issues_filtered_parser(args, &storage, &handler);
+    issues.sort_by(|a, b| {
+        kinds
+            .priority_rank(b.priority())
+            .cmp(&kinds.priority_rank(a.priority()))
+            .then(a.creation_date().cmp(&b.creation_date()))
+    });
+
+    let columns = board::build(statuses, &limits, &issues);
+    display::board(&columns, hashes::abbreviation_length(&handler), &kinds);
 }
 
-fn datetime_parser(dt: &str) -> i64 {
-    match Local.datetime_from_str(dt, "%Y-%m-%d %H:%M") {
-        Ok(v) => v.timestamp(),
+pub fn tui(_args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let kinds = Kinds::new(storage.config());
+    let settings = settings_parser(&storage);
+    let author = user_parser(&storage);
+
+    let mut terminal = match Terminal::enter() {
+        Ok(v) => v,
         Err(e) => {
-            eprintln!("Error parsing date time: {}, {}", dt, e);
+            eprintln!("Error setting up terminal: {}", e);
             process::exit(-1);
         }
-    }
-}
+    };
+    let mut view = View::default();
+    // writes replace issues and may move them, so the selected issue is kept by hash unless
+    // a key changed the selected row
+    let mut kept: Option<(usize, String)> = None;
 
-fn priority_parser(args: &ArgMatches) -> Option<IssuePriority> {
-    // TODO: implement handling multiple priorities as argument
-    args.value_of("priority")
-        .map(|v| match IssuePriority::from_str(v) {
-            Ok(v) => v,
-            Err(e) => {
-                eprintln!("Error parsing issue priority: {}, {}", v, e);
+    loop {
+        let (selected, count) = {
+            let issues = match tui_issues_parser(&handler, &kinds, &view.filter) {
+                Ok(v) => v,
+                Err(e) => {
+                    view.message(&format!("Error parsing query: {}", e));
+                    view.filter.clear();
+                    continue;
+                }
+            };
+            let index = match kept {
+                Some((index, ref hash)) if index == view.selected() => issues
+                    .iter()
+                    .position(|issue| issue.hash().to_string() == *hash)
+                    .unwrap_or(index),
+                _ => view.selected(),
+            };
+            view.select(index, issues.len());
+
+            let len = hashes::abbreviation_length(&handler);
+            let rows: Vec<String> = issues
+                .iter()
+                .map(|issue| {
+                    let abbrev: String = issue.hash().to_string().chars().take(len).collect();
+                    tui::row(issue, &abbrev)
+                })
+                .collect();
+            let detail = match issues.get(view.selected()) {
+                Some(issue) => {
+                    let relations = links::relations(&handler, issue);
+                    display::issue_long_lines(issue, &relations, &kinds, settings.date_format())
+                }
+                None => vec![],
+            };
+            let total = handler.issues_filtered(&IssueFilter::new()).len();
+            let header = format!(
+                "cobweb | {} of {} issues | filter: {}",
+                issues.len(),
+                total,
+                if view.filter.is_empty() {
+                    "-"
+                } else {
+                    &view.filter
+                }
+            );
+            if let Err(e) = terminal.draw(&mut view, &header, &rows, &detail) {
+                drop(terminal);
+                eprintln!("Error drawing terminal: {}", e);
                 process::exit(-1);
             }
-        })
-}
 
-fn status_parser(args: &ArgMatches) -> Option<IssueStatus> {
-    // TODO: implement handling multiple statuses as argument
-    args.value_of("status")
-        .map(|v| match IssueStatus::from_str(v) {
-            Ok(v) => v,
+            let selected = issues.get(view.selected()).map(|issue| (*issue).to_owned());
+            kept = selected
+                .as_ref()
+                .map(|issue| (view.selected(), issue.hash().to_string()));
+            (selected, issues.len())
+        };
+
+        let key = match terminal.key() {
+            Ok(Some(v)) => v,
+            Ok(None) => continue,
             Err(e) => {
-                eprintln!("Error parsing issue status: {}, {}", v, e);
+                drop(terminal);
+                eprintln!("Error reading terminal input: {}", e);
                 process::exit(-1);
             }
-        })
-}
-
-fn issue_hash_parser(handler: &IssuesHandler, args: &ArgMatches) -> Issue {
-    let hash = hash_parser(args).unwrap();
+        };
 
-    match handler.issue(&hash) {
-        Some(v) => v.to_owned(),
-        None => {
-            eprintln!("Issue with hash {} doesn't exist", hash);
+        let result = match (view.handle(key, count), selected) {
+            (Action::Quit, _) => break,
+            (Action::Reload, _) => {
+                let mut reloaded = IssuesHandler::new();
+                reloaded
+                    .read_issues(&storage)
+                    .map(|_| handler = reloaded)
+                    .map_err(|e| format!("Reading issues failed due to: {}", e))
+            }
+            (Action::Prompt(prompt), Some(issue)) => {
+                match prompt {
+                    Prompt::Status => {
+                        // states permitted by the workflow, all states without one
+                        let mut states: Vec<String> =
+                            workflow::next_states(storage.config(), issue.status(), "edit")
+                                .iter()
+                                .map(|v| v.to_string())
+                                .collect();
+                        if states.is_empty() {
+                            states = Status::variants().iter().map(|v| v.to_string()).collect();
+                        }
+                        view.prompt(prompt, &format!("status ({})", states.join(", ")), "");
+                    }
+                    Prompt::Priority => {
+                        let label = format!("priority ({})", kinds.priorities().join(", "));
+                        view.prompt(prompt, &label, "");
+                    }
+                    Prompt::Assign => {
+                        let assignee = issue.assigned_to().to_owned().unwrap_or_default();
+                        view.prompt(prompt, "assign to (empty unassigns)", &assignee);
+                    }
+                    Prompt::Child => view.prompt(prompt, "title of the child issue", ""),
+                    Prompt::Filter => {}
+                }
+                Ok(())
+            }
+            (Action::Input(Prompt::Filter, v), _) => {
+                view.filter = v.trim().to_string();
+                view.select(0, count);
+                Ok(())
+            }
+            (Action::Input(Prompt::Child, v), Some(issue)) => tui_child_creator(
+                &mut handler,
+                &storage,
+                &kinds,
+                &settings,
+                &author,
+                &issue,
+                v.trim(),
+            ),
+            (Action::Input(prompt, v), Some(issue)) => tui_input_applier(
+                &mut handler,
+                &storage,
+                &kinds,
+                &author,
+                &issue,
+                prompt,
+                v.trim(),
+            ),
+            (Action::Describe, Some(issue)) => {
+                // the editor needs the regular terminal
+                terminal
+                    .suspend()
+                    .map(|_| editor_parser(issue.description(), "description", &storage))
+                    .and_then(|text| terminal.resume().map(|_| text))
+                    .and_then(|text| {
+                        tui_issue_updater(&mut handler, &storage, &author, &issue, |v| {
+                            v.set_description(Some(text));
+                            Ok(())
+                        })
+                    })
+            }
+            _ => Ok(()),
+        };
+        if let Err(e) = result {
+            view.message(&e);
+        }
+    }
+}
+
+/// Issues matching a query of the list subcommand, all issues for an empty query
+fn tui_issues_parser<'a>(
+    handler: &'a IssuesHandler,
+    kinds: &Kinds,
+    query: &str,
+) -> Result<Vec<&'a Issue>, String> {
+    let matches = if query.is_empty() {
+        None
+    } else {
+        Some(Query::parse(query, handler, kinds)?.evaluate(handler))
+    };
+
+    let mut issues: Vec<&Issue> = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .map(|(_, issue)| issue)
+        .filter(|issue| match matches {
+            Some(ref v) => v.contains(&issue.hash().to_string()),
+            None => true,
+        })
+        .collect();
+    issues.sort_by_key(|issue| issue.creation_date());
+    Ok(issues)
+}
+
+/// Applies a confirmed prompt of the interactive mode to the selected issue
+fn tui_input_applier(
+    handler: &mut IssuesHandler,
+    storage: &IssueStorage,
+    kinds: &Kinds,
+    author: &str,
+    issue: &Issue,
+    prompt: Prompt,
+    v: &str,
+) -> Result<(), String> {
+    match prompt {
+        Prompt::Status => {
+            let status = IssueStatus::from_str(v)
+                .map_err(|e| format!("Error parsing issue status: {}, {}", v, e))?;
+            tui_issue_updater(handler, storage, author, issue, |issue| {
+                issue.set_status(status);
+                Ok(())
+            })
+        }
+        Prompt::Priority => {
+            let priority = kinds.priority_parser(v)?;
+            tui_issue_updater(handler, storage, author, issue, |issue| {
+                issue.set_priority(priority);
+                Ok(())
+            })
+        }
+        Prompt::Assign => tui_issue_updater(handler, storage, author, issue, |issue| {
+            issue.set_assigned_to(if v.is_empty() {
+                None
+            } else {
+                Some(v.to_string())
+            });
+            Ok(())
+        }),
+        // child issues are created by tui_child_creator
+        Prompt::Child | Prompt::Filter => Ok(()),
+    }
+}
+
+/// Creates a child issue of the selected issue with the configured defaults
+fn tui_child_creator(
+    handler: &mut IssuesHandler,
+    storage: &IssueStorage,
+    kinds: &Kinds,
+    settings: &Settings,
+    author: &str,
+    issue: &Issue,
+    title: &str,
+) -> Result<(), String> {
+    if title.is_empty() {
+        return Err("No issue title provided".to_string());
+    }
+    let mut child = Issue::new(author, title);
+    issue_defaults_setter(&mut child, kinds, settings)
+        .map_err(|e| format!("Error parsing configured defaults: {}", e))?;
+    child.set_parent(Some(issue.hash().to_owned()));
+
+    handler.insert_issue(child);
+    progress_rollup(handler, storage, author);
+    issues_writer(handler, storage).map_err(|e| format!("Error writing issues: {}", e))
+}
+
+/// Changes an issue the way edit does, checking the workflow and recording the changes
+fn tui_issue_updater<F>(
+    handler: &mut IssuesHandler,
+    storage: &IssueStorage,
+    author: &str,
+    original: &Issue,
+    change: F,
+) -> Result<(), String>
+where
+    F: FnOnce(&mut Issue) -> Result<(), String>,
+{
+    let mut issue = original.to_owned();
+    change(&mut issue)?;
+
+    // check status change against the workflow
+    workflow::check(storage.config(), original.status(), &issue, "edit")
+        .map_err(|e| format!("Error changing status of issue {}: {}", issue.hash(), e))?;
+
+    // record changes
+    issue_changes_recorder(original, &mut issue, author);
+
+    handler.insert_issue(issue);
+    progress_rollup(handler, storage, author);
+    issues_writer(handler, storage).map_err(|e| format!("Error writing issues: {}", e))
+}
+
+pub fn milestone(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+    let mut milestones = milestones_parser(&storage);
+
+    match args.subcommand() {
+        ("add", Some(args)) => {
+            let name = args.value_of("name").unwrap().trim();
+            if name.is_empty() {
+                eprintln!("Error milestone name must not be empty");
+                process::exit(-1);
+            }
+            // names are looked up ignoring case
+            if milestones
+                .iter()
+                .any(|v| v.name().eq_ignore_ascii_case(name))
+            {
+                eprintln!("Error milestone {} already exists", name);
+                process::exit(-1);
+            }
+            let mut milestone = Milestone::new(name);
+            if let Some(v) = args.value_of("description") {
+                milestone.set_description(Some(v.to_string()));
+            }
+            if let Some(v) = args.value_of("target_date") {
+                let (_, ts) = datetime_range_parser(v);
+                milestone.set_target_date(Some(ts));
+            }
+            milestones.push(milestone);
+        }
+        ("edit", Some(args)) => {
+            let name = milestone_name_parser(&milestones, args.value_of("name").unwrap());
+            let rename = args.value_of("rename").map(|v| v.trim().to_string());
+            if let Some(ref v) = rename {
+                // other milestones only, the case of the name itself may change
+                let exists = milestones
+                    .iter()
+                    .any(|m| *m.name() != name && m.name().eq_ignore_ascii_case(v));
+                if v.is_empty() || exists {
+                    eprintln!("Error invalid or already existing milestone name: {}", v);
+                    process::exit(-1);
+                }
+            }
+
+            let milestone = milestones.iter_mut().find(|v| *v.name() == name).unwrap();
+            if let Some(v) = args.value_of("description") {
+                milestone.set_description(Some(v.to_string()).filter(|v| !v.is_empty()));
+            }
+            if let Some(v) = args.value_of("target_date") {
+                if v.trim().is_empty() {
+                    milestone.set_target_date(None);
+                } else {
+                    let (_, ts) = datetime_range_parser(v);
+                    milestone.set_target_date(Some(ts));
+                }
+            }
+            if let Some(v) = rename {
+                milestone.set_name(v.clone());
+                milestone_issues_mover(&storage, &mut handler, &name, Some(v));
+            }
+        }
+        ("remove", Some(args)) => {
+            let name = milestone_name_parser(&milestones, args.value_of("name").unwrap());
+            let assigned = handler
+                .issues_filtered(&IssueFilter::new())
+                .into_iter()
+                .filter(|(_, issue)| issue.milestone().as_ref() == Some(&name))
+                .count();
+            if assigned > 0 && !args.is_present("force") {
+                eprintln!(
+                    "Error milestone {} has {} assigned issues, use --force to unassign them",
+                    name, assigned
+                );
+                process::exit(-1);
+            }
+            milestone_issues_mover(&storage, &mut handler, &name, None);
+            milestones.retain(|v| *v.name() != name);
+        }
+        _ => {
+            let issues = handler.issues_filtered(&IssueFilter::new());
+            let now = Local::now().timestamp();
+            let summaries: Vec<Summary> = milestones
+                .iter()
+                .map(|milestone| {
+                    let issues: Vec<&Issue> = issues
+                        .iter()
+                        .map(|(_, issue)| *issue)
+                        .filter(|issue| issue.milestone().as_ref() == Some(milestone.name()))
+                        .collect();
+                    milestones::summary(milestone, &issues, now)
+                })
+                .collect();
+            display::milestones(&summaries, settings_parser(&storage).date_format());
+            return;
+        }
+    }
+
+    if let Err(e) = storage.write_milestones(&milestones) {
+        eprintln!("Error writing milestones: {}", e);
+        process::exit(-1);
+    }
+    store_issues(&handler, &storage);
+}
+
+/// Moves all issues of a milestone to another one or removes their milestone
+fn milestone_issues_mover(
+    storage: &IssueStorage,
+    handler: &mut IssuesHandler,
+    from: &str,
+    to: Option<String>,
+) {
+    let author = user_parser(storage);
+    let issues: Vec<Issue> = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .filter(|(_, issue)| issue.milestone().as_ref().map(|v| v.as_str()) == Some(from))
+        .map(|(_, issue)| issue.to_owned())
+        .collect();
+    for mut issue in issues {
+        let original = issue.clone();
+        issue.set_milestone(to.clone());
+        issue_changes_recorder(&original, &mut issue, &author);
+        handler.insert_issue(issue);
+    }
+}
+
+pub fn log(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let mut issue = issue_hash_parser(&handler, args);
+
+    // set work log author
+    let author = author_parser(args, &storage);
+
+    let duration = duration_parser(args.value_of("duration").unwrap());
+    if duration == 0 {
+        eprintln!("Error logging empty duration");
+        process::exit(-1);
+    }
+    let date = match args.value_of("date") {
+        Some(v) => datetime_parser(v),
+        None => Local::now().timestamp(),
+    };
+
+    issue.add_work_log(IssueWorkLog::new(&author, date, duration));
+
+    handler.insert_issue(issue);
+    store_issues(&handler, &storage);
+}
+
+pub fn timer(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let user = user_parser(&storage);
+    let mut timers = match worklog::timers(storage.path()) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error reading timers: {}", e);
+            process::exit(-1);
+        }
+    };
+    let now = Local::now().timestamp();
+
+    match args.subcommand() {
+        ("start", Some(args)) => {
+            let issue = issue_hash_parser(&handler, args);
+            if let Some(timer) = timers.get(&user) {
+                eprintln!(
+                    "Error timer for issue {} is already running, stop it first",
+                    timer.hash
+                );
+                process::exit(-1);
+            }
+            timers.insert(
+                user,
+                Timer {
+                    hash: issue.hash().to_string(),
+                    started: now,
+                },
+            );
+        }
+        ("stop", Some(args)) => {
+            let timer = match timers.remove(&user) {
+                Some(v) => v,
+                None => {
+                    eprintln!("Error no timer is running");
+                    process::exit(-1);
+                }
+            };
+            if let Some(hash) = hash_parser(&handler, args) {
+                if hash.to_string() != timer.hash {
+                    eprintln!("Error running timer is for issue {}", timer.hash);
+                    process::exit(-1);
+                }
+            }
+            let mut issue = match IssueHash::from_str(&timer.hash)
+                .ok()
+                .and_then(|hash| handler.issue(&hash))
+            {
+                Some(v) => v.to_owned(),
+                None => {
+                    eprintln!("Issue with hash {} doesn't exist", timer.hash);
+                    process::exit(-1);
+                }
+            };
+
+            // at least one minute is logged
+            let duration = ((now - timer.started).max(60) as u64).div_ceil(60) * 60;
+            issue.add_work_log(IssueWorkLog::new(&user, timer.started, duration));
+            println!(
+                "Logged {} on issue {}",
+                worklog::format(duration),
+                timer.hash
+            );
+
+            handler.insert_issue(issue);
+            store_issues(&handler, &storage);
+        }
+        _ => {
+            match timers.get(&user) {
+                Some(timer) => println!(
+                    "Timer for issue {} running for {}",
+                    timer.hash,
+                    worklog::format((now - timer.started).max(0) as u64)
+                ),
+                None => println!("No timer running"),
+            }
+            return;
+        }
+    }
+
+    if let Err(e) = worklog::write_timers(storage.path(), &timers) {
+        eprintln!("Error writing timers: {}", e);
+        process::exit(-1);
+    }
+}
+
+pub fn time(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let since = args.value_of("since").map(|v| datetime_range_parser(v).0);
+    let until = args.value_of("until").map(|v| datetime_range_parser(v).1);
+    let logged = |issue: &Issue| -> u64 {
+        issue
+            .work_logs()
+            .iter()
+            .filter(|log| !matches!(since, Some(v) if log.date() < v))
+            .filter(|log| !matches!(until, Some(v) if log.date() > v))
+            .map(|log| log.duration())
+            .sum()
+    };
+
+    let mut rows = Vec::new();
+    let mut assignees: BTreeMap<String, TimeTotals> = BTreeMap::new();
+    for issue in issues_filtered_parser(args, &storage, &handler) {
+        let mut totals = TimeTotals {
+            logged: logged(issue),
+            estimate: issue.estimate().unwrap_or(0),
+            ..TimeTotals::default()
+        };
+        // roll up the issue and all of its descendants
+        for hash in handler.find_dependend_issues(issue.hash()) {
+            if let Some(v) = handler.issue(&hash) {
+                totals.total_logged += logged(v);
+                totals.total_estimate += v.estimate().unwrap_or(0);
+            }
+        }
+        if totals.total_logged == 0 && totals.total_estimate == 0 {
+            continue;
+        }
+
+        let assignee = issue
+            .assigned_to()
+            .to_owned()
+            .unwrap_or_else(|| "-".to_string());
+        let entry = assignees.entry(assignee).or_default();
+        entry.logged += totals.logged;
+        entry.estimate += totals.estimate;
+
+        rows.push((issue, totals));
+    }
+
+    display::time_report(&rows, &assignees);
+}
+
+pub fn sync_commits(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let range = args.value_of("range").unwrap_or("HEAD");
+    let dry_run = args.is_present("dry_run");
+
+    let log = match commits::log(working_dir, &[range]) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error reading git history: {}, {}", range, e);
+            process::exit(-1);
+        }
+    };
+
+    let mut closed = 0;
+    for commit in log {
+        for reference in commits::closing_references(&commit.message) {
+            // prefixes are not resolved, a hex word of a message may match any issue
+            let hash = match IssueHash::from_str(&reference) {
+                Ok(v) => v,
+                Err(_) => continue,
+            };
+            let mut issue = match handler.issue(&hash) {
+                Some(v) => v.to_owned(),
+                None => continue,
+            };
+            // commits already synced are skipped which keeps reruns idempotent
+            if issue.commits().contains(&commit.id) {
+                continue;
+            }
+
+            let original = issue.clone();
+            issue.add_commit(commit.id.clone());
+            issue_closer(&mut issue);
+            if let Err(e) = workflow::check(storage.config(), original.status(), &issue, "close") {
+                eprintln!("Skip issue {}: {}", hash, e);
+                continue;
+            }
+            issue_changes_recorder(&original, &mut issue, &commit.author);
+
+            print!("Closed by {}: ", &commit.id[..commit.id.len().min(12)]);
+            display::issue_short(&issue, &hashes::abbreviation(&handler, &hash));
+
+            handler.insert_issue(issue);
+            closed += 1;
+        }
+    }
+
+    if dry_run {
+        println!("Dry run, would sync {} issues", closed);
+        return;
+    }
+    if closed == 1 {
+        println!("Synced 1 issue");
+    } else {
+        println!("Synced {} issues", closed);
+    }
+    if closed > 0 {
+        progress_rollup(&mut handler, &storage, &user_parser(&storage));
+        store_issues(&handler, &storage);
+    }
+}
+
+pub fn hooks(args: &ArgMatches, working_dir: &PathBuf) {
+    match args.subcommand() {
+        ("install", Some(args)) => hooks_install(args, working_dir),
+        ("check-message", Some(args)) => hooks_check_message(args, working_dir),
+        ("check-push", Some(_)) => hooks_check_push(working_dir),
+        _ => {}
+    }
+}
+
+fn hooks_install(args: &ArgMatches, working_dir: &PathBuf) {
+    // make sure hooks are only installed for tracked repositories
+    let _ = load_issues(working_dir);
+
+    let hooks_dir = match commits::hooks_dir(working_dir) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error finding git hooks directory: {}", e);
+            process::exit(-1);
+        }
+    };
+    let exe = match env::current_exe() {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error retrieving cobweb executable path: {}", e);
+            process::exit(-1);
+        }
+    };
+
+    let mut hooks = vec![(
+        "commit-msg",
+        format!(
+            "#!/bin/sh\n# installed by cobweb\nexec \"{}\" hooks check-message \"$1\"\n",
+            exe.display()
+        ),
+    )];
+    if args.is_present("pre_push") {
+        hooks.push((
+            "pre-push",
+            format!(
+                "#!/bin/sh\n# installed by cobweb\nexec \"{}\" hooks check-push\n",
+                exe.display()
+            ),
+        ));
+    }
+
+    for (name, script) in hooks {
+        let path = hooks_dir.join(name);
+        if path.exists() && !args.is_present("force") {
+            eprintln!(
+                "Error hook {} already exists, use --force to overwrite it",
+                path.display()
+            );
+            process::exit(-1);
+        }
+        if let Err(e) = fs::create_dir_all(&hooks_dir).and_then(|_| fs::write(&path, script)) {
+            eprintln!("Error writing hook {}: {}", path.display(), e);
+            process::exit(-1);
+        }
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let ret = fs::set_permissions(&path, fs::Permissions::from_mode(0o755));
+            if let Err(e) = ret {
+                eprintln!("Error making hook {} executable: {}", path.display(), e);
+                process::exit(-1);
+            }
+        }
+        println!("Installed {}", path.display());
+    }
+}
+
+fn hooks_check_message(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let path = args.value_of("file").unwrap();
+    let message = match fs::read_to_string(path) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error reading commit message: {}, {}", path, e);
+            process::exit(-1);
+        }
+    };
+    // lines starting with # are stripped by git
+    let message: Vec<&str> = message
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .collect();
+
+    let problems = reference_problems(&storage, &handler, &message.join("\n"), None);
+    if !problems.is_empty() {
+        eprintln!("Commit rejected by cobweb:");
+        for problem in problems {
+            eprintln!("  {}", problem);
+        }
+        process::exit(1);
+    }
+}
+
+fn hooks_check_push(working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let mut input = String::new();
+    if let Err(e) = stdin().read_to_string(&mut input) {
+        eprintln!("Error reading pushed refs: {}", e);
+        process::exit(-1);
+    }
+
+    let mut rejected = false;
+    // each line is: <local ref> <local sha> <remote ref> <remote sha>
+    for line in input.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        if fields.len() != 4 {
+            continue;
+        }
+        let (local_sha, remote_sha) = (fields[1], fields[3]);
+        let deleted = |sha: &str| sha.chars().all(|c| c == '0');
+        if deleted(local_sha) {
+            continue;
+        }
+        let range = format!("{}..{}", remote_sha, local_sha);
+        let range: Vec<&str> = if deleted(remote_sha) {
+            vec![local_sha, "--not", "--remotes"]
+        } else {
+            vec![&range]
+        };
+
+        let log = match commits::log(working_dir, &range) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Error reading pushed commits: {}", e);
+                process::exit(-1);
+            }
+        };
+        for commit in log {
+            let problems =
+                reference_problems(&storage, &handler, &commit.message, Some(&commit.id));
+            if !problems.is_empty() {
+                eprintln!("Commit {} rejected by cobweb:", commit.id);
+                for problem in problems {
+                    eprintln!("  {}", problem);
+                }
+                rejected = true;
+            }
+        }
+    }
+
+    if rejected {
+        process::exit(1);
+    }
+}
+
+/// Checks issue references of a commit message, issues closed by the
+/// commit itself are accepted
+fn reference_problems(
+    storage: &IssueStorage,
+    handler: &IssuesHandler,
+    message: &str,
+    commit: Option<&str>,
+) -> Vec<String> {
+    commits::reference_problems(message, &|reference| {
+        // only full hashes count, prefixes are not resolved
+        let hash = IssueHash::from_str(reference).map_err(|e| e.to_string())?;
+        if !storage.issue_exists(&hash) {
+            return Ok(None);
+        }
+        Ok(handler.issue(&hash).map(|issue| {
+            let closed_by_commit = match commit {
+                Some(v) => issue.commits().iter().any(|c| c == v),
+                None => false,
+            };
+            if closed_by_commit {
+                IssueStatus::Open
+            } else {
+                issue.status().clone()
+            }
+        }))
+    })
+}
+
+pub fn export(_args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, handler) = load_issues(working_dir);
+
+    let issues: Vec<&Issue> = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .map(|(_, issue)| issue)
+        .collect();
+
+    // attachment contents are archived so imports into other trackers are complete
+    let mut contents = BTreeMap::new();
+    for attachment in issues.iter().flat_map(|issue| issue.attachments()) {
+        let digest = attachment.digest().to_string();
+        if contents.contains_key(&digest) {
+            continue;
+        }
+        match attachments::read(storage.path(), &digest) {
+            Ok(v) => contents.insert(digest, v),
+            Err(e) => {
+                eprintln!("Error reading attachment {}: {}", attachment.name(), e);
+                process::exit(-1);
+            }
+        };
+    }
+
+    match output::archive(&issues, &contents) {
+        Ok(v) => println!("{}", v),
+        Err(e) => {
+            eprintln!("Error serializing issues: {}", e);
+            process::exit(-1);
+        }
+    }
+}
+
+pub fn import(args: &ArgMatches, working_dir: &PathBuf) {
+    let (storage, mut handler) = load_issues(working_dir);
+
+    let (issues, contents) = archive_parser(args);
+    let dry_run = args.is_present("dry_run");
+    let on_conflict = args.value_of("on_conflict").unwrap_or("fail");
+
+    // every parent has to be part of the archive or the tracker
+    let hashes: Vec<String> = issues.iter().map(|v| v.hash().to_string()).collect();
+    for issue in &issues {
+        if let Some(ref parent) = *issue.parent() {
+            if !hashes.contains(&parent.to_string()) && handler.issue(parent).is_none() {
+                eprintln!(
+                    "Error parent issue hash {} of issue {} doesn't exist",
+                    parent,
+                    issue.hash()
+                );
+                process::exit(-1);
+            }
+        }
+    }
+
+    let conflicts: Vec<&Issue> = issues
+        .iter()
+        .filter(|v| handler.issue(v.hash()).is_some())
+        .collect();
+    if on_conflict == "fail" && !conflicts.is_empty() {
+        eprintln!("Following issues already exist, nothing imported:");
+        for issue in conflicts {
+            display::issue_short(issue, &hashes::abbreviation(&handler, issue.hash()));
+        }
+        process::exit(-1);
+    }
+
+    // contents of imported attachments have to be part of the archive or the tracker,
+    // archives before version 2 don't contain any
+    for issue in &issues {
+        if on_conflict == "skip" && handler.issue(issue.hash()).is_some() {
+            continue;
+        }
+        for attachment in issue.attachments() {
+            let digest = attachment.digest().to_string();
+            if !contents.contains_key(&digest)
+                && !attachments::path(storage.path(), &digest).exists()
+            {
+                eprintln!(
+                    "Error content of attachment {} of issue {} is missing in the archive",
+                    attachment.name(),
+                    issue.hash()
+                );
+                process::exit(-1);
+            }
+        }
+    }
+
+    let mut imported = 0;
+    let mut skipped = 0;
+    let mut digests = BTreeSet::new();
+    for issue in issues {
+        let exists = handler.issue(issue.hash()).is_some();
+        if exists && on_conflict == "skip" {
+            if dry_run {
+                print!("Skip: ");
+                display::issue_short(&issue, &hashes::abbreviation(&handler, issue.hash()));
+            }
+            skipped += 1;
+            continue;
+        }
+        if dry_run {
+            print!("{}", if exists { "Overwrite: " } else { "Import: " });
+            display::issue_short(&issue, &hashes::abbreviation(&handler, issue.hash()));
+        }
+        digests.extend(issue.attachments().iter().map(|v| v.digest().to_string()));
+        handler.insert_issue(issue);
+        imported += 1;
+    }
+
+    if dry_run {
+        println!(
+            "Dry run, would import {} and skip {} issues",
+            imported, skipped
+        );
+        return;
+    }
+    for (digest, data) in contents.iter().filter(|(v, _)| digests.contains(*v)) {
+        if let Err(e) = attachments::store_data(storage.path(), data) {
+            eprintln!("Error storing attachment {}: {}", digest, e);
+            process::exit(-1);
+        }
+    }
+    store_issues(&handler, &storage);
+    // overwritten issues may leave attachments no issue references anymore
+    attachments_cleaner(&handler, &storage);
+    println!("Imported {} and skipped {} issues", imported, skipped);
+}
+
+pub fn config(args: &ArgMatches, working_dir: &PathBuf) {
+    match args.subcommand() {
+        ("get", Some(args)) => config_get(args, working_dir),
+        ("set", Some(args)) => config_set(args, working_dir),
+        ("unset", Some(args)) => config_unset(args, working_dir),
+        ("edit", Some(args)) => config_edit(args, working_dir),
+        ("list", Some(args)) => config_list(args, working_dir),
+        _ => config_list(args, working_dir),
+    }
+}
+
+fn config_list(args: &ArgMatches, working_dir: &PathBuf) {
+    let storage = storage_parser(working_dir);
+    let settings = settings_parser(&storage);
+
+    match format_parser(args) {
+        Format::Text => display::config(storage.config(), &settings),
+        format => output::config(&settings, format),
+    }
+}
+
+fn config_get(args: &ArgMatches, working_dir: &PathBuf) {
+    let storage = storage_parser(working_dir);
+    let settings = settings_parser(&storage);
+
+    let key = args.value_of("key").unwrap().to_string();
+    match settings.get(&key) {
+        Some((value, layer)) if args.is_present("origin") => println!("{}\t{}", layer, value),
+        Some((value, _)) => println!("{}", value),
+        // unset keys exit with 1 like git config
+        None => process::exit(1),
+    }
+}
+
+fn config_set(args: &ArgMatches, working_dir: &PathBuf) {
+    let mut storage = storage_parser(working_dir);
+    let settings = settings_parser(&storage);
+
+    let key = args.value_of("key").unwrap().to_string();
+    let value = args.value_of("value").unwrap();
+    let value = match settings::validate(&key, value, &Kinds::new(storage.config())) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error setting {}: {}", key, e);
+            process::exit(-1);
+        }
+    };
+
+    if args.is_present("global") {
+        let mut values = settings.layer(Layer::Global).to_owned();
+        values.insert(key, value);
+        global_settings_writer(&values);
+    } else {
+        storage.config_mut().set_setting(key, value);
+        repository_settings_writer(&storage);
+    }
+}
+
+fn config_unset(args: &ArgMatches, working_dir: &PathBuf) {
+    let mut storage = storage_parser(working_dir);
+    let settings = settings_parser(&storage);
+
+    let key = args.value_of("key").unwrap().to_string();
+    if args.is_present("global") {
+        let mut values = settings.layer(Layer::Global).to_owned();
+        values.remove(&key);
+        global_settings_writer(&values);
+    } else {
+        storage.config_mut().unset_setting(&key);
+        repository_settings_writer(&storage);
+    }
+}
+
+fn config_edit(args: &ArgMatches, working_dir: &PathBuf) {
+    let mut storage = storage_parser(working_dir);
+    let settings = settings_parser(&storage);
+
+    let layer = if args.is_present("global") {
+        Layer::Global
+    } else {
+        Layer::Repository
+    };
+    let original = settings.layer(layer).to_owned();
+
+    // open editor with the current values of the layer and the possible keys
+    let mut text = format!("# {} settings, lines are 'key = value'\n", layer);
+    for (key, description) in settings::KEYS {
+        text.push_str(&format!("# {}: {}\n", key, description));
+    }
+    text.push_str(&settings::render(&original));
+    let text = editor_parser(&Some(text), "config", &storage);
+
+    let values = match settings::parse(&text) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing settings: {}", e);
+            process::exit(-1);
+        }
+    };
+    let kinds = Kinds::new(storage.config());
+    let mut validated = BTreeMap::new();
+    for (key, value) in values {
+        match settings::validate(&key, &value, &kinds) {
+            Ok(v) => validated.insert(key, v),
+            Err(e) => {
+                eprintln!("Error setting {}: {}", key, e);
+                process::exit(-1);
+            }
+        };
+    }
+
+    match layer {
+        Layer::Global => global_settings_writer(&validated),
+        _ => {
+            for key in original.keys() {
+                if !validated.contains_key(key) {
+                    storage.config_mut().unset_setting(key);
+                }
+            }
+            for (key, value) in validated {
+                storage.config_mut().set_setting(key, value);
+            }
+            repository_settings_writer(&storage);
+        }
+    }
+}
+
+fn global_settings_writer(values: &BTreeMap<String, String>) {
+    if let Err(e) = settings::write_global(values) {
+        eprintln!("Error writing global settings: {}", e);
+        process::exit(-1);
+    }
+}
+
+fn repository_settings_writer(storage: &IssueStorage) {
+    if let Err(e) = storage.write_config() {
+        eprintln!("Error writing repository settings: {}", e);
+        process::exit(-1);
+    }
+}
+
+fn storage_parser(working_dir: &PathBuf) -> IssueStorage {
+    match IssueStorage::find_from_path(working_dir) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Failed finding issue tracker meta files: {}", e);
+            process::exit(-1);
+        }
+    }
+}
+
+fn load_issues(working_dir: &PathBuf) -> (IssueStorage, IssuesHandler) {
+    let storage = storage_parser(working_dir);
+
+    let mut handler = IssuesHandler::new();
+    match handler.read_issues(&storage) {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Reading issues failed due to: {}", e);
+            process::exit(-1);
+        }
+    }
+
+    (storage, handler)
+}
+
+fn store_issues(handler: &IssuesHandler, storage: &IssueStorage) {
+    match issues_writer(handler, storage) {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Error writing issues: {}", e);
+            process::exit(-1);
+        }
+    }
+}
+
+/// Writes the issues and brings the search index up to date
+fn issues_writer(handler: &IssuesHandler, storage: &IssueStorage) -> Result<(), String> {
+    handler.write_issues(storage).map_err(|e| e.to_string())?;
+    search_index_updater(handler, storage)
+        .map(|_| ())
+        .map_err(|e| format!("search index: {}", e))
+}
+
+/// Brings the search index up to date with the issues, only changed issues are reindexed
+fn search_index_updater(handler: &IssuesHandler, storage: &IssueStorage) -> Result<Index, String> {
+    let mut index = Index::read(storage.path())?;
+    let issues: Vec<&Issue> = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .map(|(_, issue)| issue)
+        .collect();
+    if index.update(&issues) {
+        index.write(storage.path())?;
+    }
+    Ok(index)
+}
+
+/// Removes links to removed issues and records the removals in the linking issues
+fn links_cleaner(handler: &mut IssuesHandler, removed: &[IssueHash], author: &str) {
+    let removed: BTreeSet<String> = removed.iter().map(|v| v.to_string()).collect();
+    let mut changed = Vec::new();
+    for (_, issue) in handler.issues_filtered(&IssueFilter::new()) {
+        let dangling: Vec<(IssueLinkKind, IssueHash)> = issue
+            .links()
+            .iter()
+            .filter(|v| removed.contains(&v.target().to_string()))
+            .map(|v| (v.kind(), v.target().to_owned()))
+            .collect();
+        if dangling.is_empty() {
+            continue;
+        }
+        let mut issue = issue.to_owned();
+        for (kind, target) in dangling {
+            issue.remove_link(kind, &target);
+            let change = format!("{} {}", links::name(kind), target);
+            issue.add_change(IssueChange::new("links", Some(change), None, author));
+        }
+        changed.push(issue);
+    }
+    for issue in changed {
+        handler.insert_issue(issue);
+    }
+}
+
+/// Removes stored attachment contents no issue references anymore
+fn attachments_cleaner(handler: &IssuesHandler, storage: &IssueStorage) {
+    let referenced: BTreeSet<String> = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .flat_map(|(_, issue)| issue.attachments().iter().map(|v| v.digest().to_string()))
+        .collect();
+    if let Err(e) = attachments::clean(storage.path(), &referenced) {
+        eprintln!("Error removing unreferenced attachments: {}", e);
+        process::exit(-1);
+    }
+}
+
+fn settings_parser(storage: &IssueStorage) -> Settings {
+    match Settings::load(storage.config()) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error reading settings: {}", e);
+            process::exit(-1);
+        }
+    }
+}
+
+fn milestones_parser(storage: &IssueStorage) -> Vec<Milestone> {
+    match storage.read_milestones() {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error reading milestones: {}", e);
+            process::exit(-1);
+        }
+    }
+}
+
+fn milestone_name_parser(milestones: &[Milestone], v: &str) -> String {
+    let name = v.trim();
+    match milestones
+        .iter()
+        .find(|m| m.name().eq_ignore_ascii_case(name))
+    {
+        Some(m) => m.name().to_string(),
+        None => {
+            eprintln!("Error milestone {} doesn't exist", name);
+            process::exit(-1);
+        }
+    }
+}
+
+fn author_parser(args: &ArgMatches, storage: &IssueStorage) -> String {
+    match args.value_of("author") {
+        Some(v) => v.to_owned(),
+        None => user_parser(storage),
+    }
+}
+
+fn user_parser(storage: &IssueStorage) -> String {
+    if let Some(v) = settings_parser(storage).value("user") {
+        v.to_owned()
+    } else {
+        match user::get_user_name() {
+            Ok(user) => user,
+            Err(e) => {
+                println!("Error retrieving user: {}", e);
+                process::exit(-1);
+            }
+        }
+    }
+}
+
+fn hash_parser(handler: &IssuesHandler, args: &ArgMatches) -> Option<IssueHash> {
+    args.value_of("hash")
+        .map(|v| match hashes::resolve(handler, v) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Error resolving issue hash: {}", e);
+                process::exit(-1);
+            }
+        })
+}
+
+/// Reads the issues and the attachment contents keyed by their digest of an archive
+fn archive_parser(args: &ArgMatches) -> (Vec<Issue>, BTreeMap<String, Vec<u8>>) {
+    let path = args.value_of("file").unwrap();
+    let mut data = String::new();
+    let ret = if path == "-" {
+        stdin().read_to_string(&mut data)
+    } else {
+        fs::File::open(path).and_then(|mut v| v.read_to_string(&mut data))
+    };
+    if let Err(e) = ret {
+        eprintln!("Error reading archive: {}, {}", path, e);
+        process::exit(-1);
+    }
+
+    let mut archive: serde_json::Value = match serde_json::from_str(&data) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing archive: {}, {}", path, e);
+            process::exit(-1);
+        }
+    };
+    match archive["version"].as_u64() {
+        Some(v) if v <= output::ARCHIVE_VERSION => {}
+        Some(v) => {
+            eprintln!("Error unsupported archive version: {}", v);
+            process::exit(-1);
+        }
+        None => {
+            eprintln!("Error archive version missing: {}", path);
+            process::exit(-1);
+        }
+    }
+    let issues = match serde_json::from_value(archive["issues"].take()) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing archived issues: {}, {}", path, e);
+            process::exit(-1);
+        }
+    };
+
+    let mut contents = BTreeMap::new();
+    for (digest, data) in archive["attachments"].as_object().into_iter().flatten() {
+        match data.as_str().map(base64::decode) {
+            Some(Ok(ref v)) if attachments::digest(v) == *digest => {
+                contents.insert(digest.to_string(), v.to_owned())
+            }
+            Some(Ok(_)) => {
+                eprintln!(
+                    "Error archived attachment doesn't match its digest: {}",
+                    digest
+                );
+                process::exit(-1);
+            }
+            _ => {
+                eprintln!("Error parsing archived attachment: {}, {}", path, digest);
+                process::exit(-1);
+            }
+        };
+    }
+    (issues, contents)
+}
+
+fn format_parser(args: &ArgMatches) -> Format {
+    match args.value_of("format").map(Format::from_str) {
+        None => Format::Text,
+        Some(Ok(v)) => v,
+        Some(Err(e)) => {
+            eprintln!("Error parsing output format: {}", e);
+            process::exit(-1);
+        }
+    }
+}
+
+fn sort_parser(v: &str) -> Vec<(Field, bool)> {
+    v.split(',')
+        .map(|key| {
+            let mut parts = key.splitn(2, ':');
+            let field = parts.next().unwrap_or("");
+            let field = match Field::from_str(field) {
+                Ok(v) => v,
+                Err(e) => {
+                    eprintln!("Error parsing sort key: {}, {}", key, e);
+                    process::exit(-1);
+                }
+            };
+            let descending = match parts.next().map(|v| v.trim().to_lowercase()) {
+                None => false,
+                Some(ref v) if v == "asc" => false,
+                Some(ref v) if v == "desc" => true,
+                Some(v) => {
+                    eprintln!("Error parsing sort direction: {}, expected asc or desc", v);
+                    process::exit(-1);
+                }
+            };
+            (field, descending)
+        })
+        .collect()
+}
+
+fn columns_parser(args: &ArgMatches) -> Vec<Field> {
+    let columns = args
+        .value_of("columns")
+        .unwrap_or("hash,type,status,priority,progress,due,assigned,title");
+    columns
+        .split(',')
+        .map(|v| match Field::from_str(v) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Error parsing table column: {}, {}", v, e);
+                process::exit(-1);
+            }
+        })
+        .collect()
+}
+
+fn type_parser(args: &ArgMatches, kinds: &Kinds) -> Option<IssueType> {
+    // TODO: implement handling multiple types as argument
+    args.value_of("type").map(|v| match kinds.type_parser(v) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing issue type: {}, {}", v, e);
+            process::exit(-1);
+        }
+    })
+}
+
+fn datetime_parser(dt: &str) -> i64 {
+    let (ts, _) = datetime_range_parser(dt);
+    ts
+}
+
+/// Parses dates into the first and last timestamp they cover, e.g. a whole day for 'tomorrow'
+fn datetime_range_parser(dt: &str) -> (i64, i64) {
+    match dates::parse(dt, Local::now()) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing date time: {}", e);
+            process::exit(-1);
+        }
+    }
+}
+
+fn priority_parser(args: &ArgMatches, kinds: &Kinds) -> Option<IssuePriority> {
+    // TODO: implement handling multiple priorities as argument
+    args.value_of("priority")
+        .map(|v| match kinds.priority_parser(v) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Error parsing issue priority: {}, {}", v, e);
+                process::exit(-1);
+            }
+        })
+}
+
+fn status_parser(args: &ArgMatches) -> Option<IssueStatus> {
+    // TODO: implement handling multiple statuses as argument
+    args.value_of("status")
+        .map(|v| match IssueStatus::from_str(v) {
+            Ok(v) => v,
+            Err(e) => {
+                eprintln!("Error parsing issue status: {}, {}", v, e);
+                process::exit(-1);
+            }
+        })
+}
+
+fn issue_hash_parser(handler: &IssuesHandler, args: &ArgMatches) -> Issue {
+    let hash = hash_parser(handler, args).unwrap();
+
+    match handler.issue(&hash) {
+        Some(v) => v.to_owned(),
+        None => {
+            eprintln!("Issue with hash {} doesn't exist", hash);
+            process::exit(-1);
+        }
+    }
+}
+
+/// Parses link kind and hashes, returns the issue holding the link and the link target.
+/// For blocked-by the link is held by the target issue.
+fn link_parser(handler: &IssuesHandler, args: &ArgMatches) -> (IssueLinkKind, Issue, IssueHash) {
+    let (kind, swapped) = match links::kind(args.value_of("kind").unwrap()) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing link type: {}", e);
+            process::exit(-1);
+        }
+    };
+
+    let issue = issue_hash_parser(handler, args);
+    let target = match hashes::resolve(handler, args.value_of("target").unwrap()) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error resolving issue hash: {}", e);
+            process::exit(-1);
+        }
+    };
+    let target = match handler.issue(&target) {
+        Some(v) => v.to_owned(),
+        None => {
+            eprintln!("Issue with hash {} doesn't exist", target);
             process::exit(-1);
         }
+    };
+    if issue.hash().to_string() == target.hash().to_string() {
+        eprintln!("Error linking issue {} to itself", issue.hash());
+        process::exit(-1);
+    }
+
+    if swapped {
+        (kind, target, issue.hash().to_owned())
+    } else {
+        (kind, issue, target.hash().to_owned())
     }
 }
 
-fn issue_type_parser(issue: &mut Issue, args: &ArgMatches) {
-    if let Some(typ) = type_parser(args) {
+/// Sets the configured default type, priority and assignee of a new issue
+fn issue_defaults_setter(
+    issue: &mut Issue,
+    kinds: &Kinds,
+    settings: &Settings,
+) -> Result<(), String> {
+    issue.set_typ(match settings.value("default_type") {
+        Some(v) => kinds.type_parser(v)?,
+        None => kinds.default_type()?,
+    });
+    issue.set_priority(match settings.value("default_priority") {
+        Some(v) => kinds.priority_parser(v)?,
+        None => kinds.default_priority()?,
+    });
+    if let Some(v) = settings.value("default_assignee") {
+        issue.set_assigned_to(Some(v.to_string()));
+    }
+    Ok(())
+}
+
+fn issue_closer(issue: &mut Issue) {
+    issue.set_status(IssueStatus::Closed);
+    let _ = issue.set_progress(100);
+}
+
+fn issue_transition_checker(
+    storage: &IssueStorage,
+    original: &Issue,
+    issue: &Issue,
+    subcommand: &str,
+    pending: &[&str],
+) {
+    let config = storage.config();
+    if let Err(e) = workflow::check_pending(config, original.status(), issue, subcommand, pending) {
+        eprintln!("Error changing status of issue {}: {}", issue.hash(), e);
+        process::exit(-1);
+    }
+}
+
+fn issue_type_parser(issue: &mut Issue, args: &ArgMatches, kinds: &Kinds) {
+    if let Some(typ) = type_parser(args, kinds) {
         issue.set_typ(typ);
     }
 }
 
-fn issue_parent_parser(issue: &mut Issue, args: &ArgMatches, storage: &IssueStorage) {
+fn issue_parent_parser(issue: &mut Issue, args: &ArgMatches, handler: &IssuesHandler) {
     if let Some(v) = args.value_of("parent") {
-        let parent = match IssueHash::from_str(v) {
+        let parent = match hashes::resolve(handler, v) {
             Ok(v) => v,
             Err(e) => {
-                eprintln!("Error parsing issue parent hash: {}, {}", v, e);
+                eprintln!("Error resolving issue parent hash: {}", e);
                 process::exit(-1);
             }
         };
-        if !storage.issue_exists(&parent) {
-            eprintln!("Error parent issue hash {} doesn't exist", parent);
-            process::exit(-1);
+        // the parent must not be the issue itself or one of its descendants
+        let mut visited = BTreeSet::new();
+        let mut ancestor = Some(parent.clone());
+        while let Some(hash) = ancestor {
+            if hash == *issue.hash() {
+                eprintln!(
+                    "Error issue {} can't be parent of issue {}, parents must not form a cycle",
+                    parent,
+                    issue.hash()
+                );
+                process::exit(-1);
+            }
+            if !visited.insert(hash.to_string()) {
+                break;
+            }
+            ancestor = handler.issue(&hash).and_then(|v| v.parent().to_owned());
         }
+
         issue.set_parent(Some(parent));
     }
 }
 
-fn issue_priority_parser(issue: &mut Issue, args: &ArgMatches) {
-    if let Some(priority) = priority_parser(args) {
+fn issue_priority_parser(issue: &mut Issue, args: &ArgMatches, kinds: &Kinds) {
+    if let Some(priority) = priority_parser(args, kinds) {
         issue.set_priority(priority);
     }
 }
@@ -455,6 +2430,18 @@ fn issue_assigned_to_parser(issue: &mut Issue, args: &ArgMatches) {
     }
 }
 
+fn issue_milestone_parser(issue: &mut Issue, args: &ArgMatches, storage: &IssueStorage) {
+    if let Some(v) = args.value_of("milestone") {
+        if v.trim().is_empty() {
+            issue.set_milestone(None);
+        } else {
+            let milestones = milestones_parser(storage);
+            let name = milestone_name_parser(&milestones, v);
+            issue.set_milestone(Some(name));
+        }
+    }
+}
+
 fn issue_start_date_parser(issue: &mut Issue, args: &ArgMatches) {
     if let Some(v) = args.value_of("start_date") {
         let ts = datetime_parser(v);
@@ -464,7 +2451,8 @@ fn issue_start_date_parser(issue: &mut Issue, args: &ArgMatches) {
 
 fn issue_due_date_parser(issue: &mut Issue, args: &ArgMatches) {
     if let Some(v) = args.value_of("due_date") {
-        let ts = datetime_parser(v);
+        // issues are due at the end of a given day
+        let (_, ts) = datetime_range_parser(v);
         issue.set_due_date(Some(ts));
     }
 }
@@ -488,80 +2476,335 @@ fn issue_progress_parser(issue: &mut Issue, args: &ArgMatches) {
     }
 }
 
-fn issue_description_edit_parser(issue: &mut Issue, args: &ArgMatches) {
-    // create temporary txt file, open corresponding editor, set description
-    if args.is_present("description_edit") {
-        // create temporary file for description
-        let mut tmp_file = match tempfile::Builder::new().suffix(".txt").tempfile() {
-            Ok(v) => v,
-            Err(e) => {
-                eprintln!("Error creating temporary description file: {}", e);
-                process::exit(-1);
-            }
-        };
+fn issue_rollup_parser(issue: &mut Issue, args: &ArgMatches) {
+    match args.value_of("rollup") {
+        Some("on") => issue.set_progress_rollup(true),
+        Some("off") => issue.set_progress_rollup(false),
+        _ => {}
+    }
+}
 
-        // write current issue description to temporary file
-        if let Some(ref description) = *issue.description() {
-            match tmp_file.write_all(description.as_bytes()) {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("Error writing issue desciption to temporary file: {}", e);
-                    let _ = tmp_file.close();
-                    process::exit(-1);
-                }
-            }
-            match tmp_file.flush() {
-                Ok(_) => {}
-                Err(e) => {
-                    eprintln!("Error syncing desciption data to filesystem: {}", e);
-                    let _ = tmp_file.close();
-                    process::exit(-1);
-                }
-            }
+/// Derives progress of issues with roll-up enabled from their children, the changes are
+/// recorded for author
+fn progress_rollup(handler: &mut IssuesHandler, storage: &IssueStorage, author: &str) {
+    let rollup_all = storage.config().progress_rollup();
+
+    let issues: BTreeMap<String, Issue> = handler
+        .issues_filtered(&IssueFilter::new())
+        .into_iter()
+        .map(|(_, issue)| (issue.hash().to_string(), issue.to_owned()))
+        .collect();
+    let mut children: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    for issue in issues.values() {
+        if let Some(ref parent) = *issue.parent() {
+            children
+                .entry(parent.to_string())
+                .or_default()
+                .push(issue.hash().to_string());
+        }
+    }
+
+    let mut progress = BTreeMap::new();
+    for (hash, issue) in &issues {
+        let value = rollup_value(hash, &issues, &children, rollup_all, &mut progress);
+        if value != issue.progress() {
+            let mut changed = issue.to_owned();
+            let _ = changed.set_progress(value);
+            issue_changes_recorder(issue, &mut changed, author);
+            handler.insert_issue(changed);
         }
+    }
+}
 
-        // try to find suitable editor from environment for description editing
-        let editor = env::var("VISUAL").ok();
-        let editor = editor.or_else(|| env::var("EDITOR").ok());
-        match editor {
-            Some(v) => {
-                let path = tmp_file.path().to_owned();
-                let ret = process::Command::new(v).arg(path).status();
+fn rollup_value(
+    hash: &str,
+    issues: &BTreeMap<String, Issue>,
+    children: &BTreeMap<String, Vec<String>>,
+    rollup_all: bool,
+    progress: &mut BTreeMap<String, u8>,
+) -> u8 {
+    if let Some(v) = progress.get(hash) {
+        return *v;
+    }
 
-                if let Err(exit_status) = ret {
-                    eprintln!("Editor doesn't exit cleanly. Exit status: {}", exit_status);
-                    process::exit(-1);
-                }
+    let issue = &issues[hash];
+    // parent cycles end at an issue already being visited, which keeps its own progress
+    progress.insert(hash.to_string(), issue.progress());
+    let value = match children.get(hash) {
+        Some(v) if rollup_all || issue.progress_rollup() => {
+            // rejected children don't contribute to the progress
+            let values: Vec<u32> = v
+                .iter()
+                .filter(|child| *issues[child.as_str()].status() != IssueStatus::Rejected)
+                .map(|child| u32::from(rollup_value(child, issues, children, rollup_all, progress)))
+                .collect();
+            if values.is_empty() {
+                issue.progress()
+            } else {
+                let count = values.len() as u32;
+                ((values.iter().sum::<u32>() + count / 2) / count) as u8
             }
-            None => {
-                eprintln!("No suitable editor found");
-                let _ = tmp_file.close();
-                process::exit(-1);
+        }
+        _ => issue.progress(),
+    };
+
+    progress.insert(hash.to_string(), value);
+    value
+}
+
+fn label_parser(v: &str) -> String {
+    let label = v.trim();
+    if label.is_empty() || label.contains(char::is_whitespace) {
+        eprintln!(
+            "Error invalid label: '{}', labels must not be empty or contain whitespaces",
+            v
+        );
+        process::exit(-1);
+    }
+    label.to_string()
+}
+
+fn issue_labels_parser(issue: &mut Issue, args: &ArgMatches) {
+    if let Some(values) = args.values_of("tag") {
+        for v in values {
+            issue.add_label(label_parser(v));
+        }
+    }
+    if let Some(values) = args.values_of("untag") {
+        for v in values {
+            issue.remove_label(&label_parser(v));
+        }
+    }
+}
+
+fn custom_field_parser(storage: &IssueStorage, v: &str) -> (String, Option<String>) {
+    match custom::parse(storage.config(), v) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing custom field: {}, {}", v, e);
+            process::exit(-1);
+        }
+    }
+}
+
+fn issue_custom_fields_parser(issue: &mut Issue, args: &ArgMatches, storage: &IssueStorage) {
+    if let Some(values) = args.values_of("field") {
+        for v in values {
+            match custom_field_parser(storage, v) {
+                (key, Some(value)) => issue.set_custom_field(key, value),
+                (key, None) => issue.remove_custom_field(&key),
             }
         }
+    }
+}
+
+fn duration_parser(v: &str) -> u64 {
+    match worklog::parse(v) {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error parsing duration: {}", e);
+            process::exit(-1);
+        }
+    }
+}
+
+fn issue_estimate_parser(issue: &mut Issue, args: &ArgMatches) {
+    if let Some(v) = args.value_of("estimate") {
+        match duration_parser(v) {
+            0 => issue.set_estimate(None),
+            v => issue.set_estimate(Some(v)),
+        }
+    }
+}
+
+fn issue_description_edit_parser(issue: &mut Issue, args: &ArgMatches, storage: &IssueStorage) {
+    // create temporary txt file, open corresponding editor, set description
+    if args.is_present("description_edit") {
+        let description = editor_parser(issue.description(), "description", storage);
+        issue.set_description(Some(description));
+    }
+}
 
-        // set cursor to start to read whole string not
-        match tmp_file.seek(SeekFrom::Start(0)) {
+fn editor_parser(text: &Option<String>, kind: &str, storage: &IssueStorage) -> String {
+    // create temporary file for text
+    let mut tmp_file = match tempfile::Builder::new().suffix(".txt").tempfile() {
+        Ok(v) => v,
+        Err(e) => {
+            eprintln!("Error creating temporary {} file: {}", kind, e);
+            process::exit(-1);
+        }
+    };
+
+    // write current text to temporary file
+    if let Some(ref text) = *text {
+        match tmp_file.write_all(text.as_bytes()) {
             Ok(_) => {}
             Err(e) => {
-                eprintln!("Error resetting file cursor: {}", e);
+                eprintln!("Error writing issue {} to temporary file: {}", kind, e);
                 let _ = tmp_file.close();
                 process::exit(-1);
             }
         }
-
-        let mut description = String::new();
-        match tmp_file.read_to_string(&mut description) {
+        match tmp_file.flush() {
             Ok(_) => {}
             Err(e) => {
-                eprintln!("Failed to extract description from temporary file: {}", e);
+                eprintln!("Error syncing {} data to filesystem: {}", kind, e);
                 let _ = tmp_file.close();
                 process::exit(-1);
             }
         }
-        let description = description.trim().to_string();
-        issue.set_description(Some(description));
+    }
+
+    // try to find suitable editor from settings or environment for text editing
+    let editor = settings_parser(storage)
+        .value("editor")
+        .map(|v| v.to_string());
+    let editor = editor.or_else(|| env::var("VISUAL").ok());
+    let editor = editor.or_else(|| env::var("EDITOR").ok());
+    match editor {
+        Some(v) => {
+            let path = tmp_file.path().to_owned();
+            let ret = process::Command::new(v).arg(path).status();
+
+            if let Err(exit_status) = ret {
+                eprintln!("Editor doesn't exit cleanly. Exit status: {}", exit_status);
+                process::exit(-1);
+            }
+        }
+        None => {
+            eprintln!("No suitable editor found");
+            let _ = tmp_file.close();
+            process::exit(-1);
+        }
+    }
+
+    // set cursor to start to read whole string not
+    match tmp_file.seek(SeekFrom::Start(0)) {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Error resetting file cursor: {}", e);
+            let _ = tmp_file.close();
+            process::exit(-1);
+        }
+    }
 
-        let _ = tmp_file.close();
+    let mut text = String::new();
+    match tmp_file.read_to_string(&mut text) {
+        Ok(_) => {}
+        Err(e) => {
+            eprintln!("Failed to extract {} from temporary file: {}", kind, e);
+            let _ = tmp_file.close();
+            process::exit(-1);
+        }
+    }
+    let _ = tmp_file.close();
+
+    text.trim().to_string()
+}
+
+fn issue_changes_recorder(original: &Issue, issue: &mut Issue, author: &str) {
+    let date = |ts: i64| Local.timestamp(ts, 0).format("%Y-%m-%d %H:%M").to_string();
+    let labels = |issue: &Issue| {
+        if issue.labels().is_empty() {
+            None
+        } else {
+            let labels: Vec<&str> = issue.labels().iter().map(|v| v.as_str()).collect();
+            Some(labels.join(", "))
+        }
+    };
+
+    let changes = vec![
+        (
+            "title",
+            Some(original.title().to_string()),
+            Some(issue.title().to_string()),
+        ),
+        (
+            "type",
+            Some(original.typ().to_string()),
+            Some(issue.typ().to_string()),
+        ),
+        (
+            "parent",
+            original.parent().as_ref().map(|v| v.to_string()),
+            issue.parent().as_ref().map(|v| v.to_string()),
+        ),
+        (
+            "author",
+            Some(original.author().to_string()),
+            Some(issue.author().to_string()),
+        ),
+        (
+            "priority",
+            Some(original.priority().to_string()),
+            Some(issue.priority().to_string()),
+        ),
+        (
+            "status",
+            Some(original.status().to_string()),
+            Some(issue.status().to_string()),
+        ),
+        (
+            "assigned to",
+            original.assigned_to().to_owned(),
+            issue.assigned_to().to_owned(),
+        ),
+        (
+            "milestone",
+            original.milestone().to_owned(),
+            issue.milestone().to_owned(),
+        ),
+        (
+            "start date",
+            Some(date(original.start_date())),
+            Some(date(issue.start_date())),
+        ),
+        (
+            "due date",
+            original.due_date().map(date),
+            issue.due_date().map(date),
+        ),
+        (
+            "progress",
+            Some(original.progress().to_string()),
+            Some(issue.progress().to_string()),
+        ),
+        (
+            "progress rollup",
+            Some(original.progress_rollup().to_string()),
+            Some(issue.progress_rollup().to_string()),
+        ),
+        ("labels", labels(original), labels(issue)),
+        (
+            "estimate",
+            original.estimate().map(worklog::format),
+            issue.estimate().map(worklog::format),
+        ),
+        (
+            "description",
+            original.description().to_owned(),
+            issue.description().to_owned(),
+        ),
+    ];
+
+    for (field, old_value, new_value) in changes {
+        if old_value != new_value {
+            issue.add_change(IssueChange::new(field, old_value, new_value, author));
+        }
+    }
+
+    // custom fields
+    let keys: BTreeSet<String> = original
+        .custom_fields()
+        .keys()
+        .chain(issue.custom_fields().keys())
+        .cloned()
+        .collect();
+    for key in keys {
+        let old_value = original.custom_fields().get(&key).cloned();
+        let new_value = issue.custom_fields().get(&key).cloned();
+        if old_value != new_value {
+            issue.add_change(IssueChange::new(&key, old_value, new_value, author));
+        }
     }
 }
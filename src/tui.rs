@@ -0,0 +1,283 @@
+use std::io::{stdout, Stdout, Write};
+
+use crossterm::cursor::{Hide, MoveTo, Show};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyModifiers};
+use crossterm::style::Print;
+use crossterm::terminal::{self, Clear, ClearType, EnterAlternateScreen, LeaveAlternateScreen};
+use crossterm::{execute, queue};
+
+use yansi::{Color, Style};
+
+use super::Issue;
+
+/// Key bindings shown in the status line
+const HELP: &str = "j/k move  J/K scroll  / filter  s status  p priority  a assign  e description  c child  r reload  q quit";
+
+/// Narrowest issue list, the detail pane takes the remaining width
+const MIN_LIST_WIDTH: usize = 30;
+
+/// Inputs asked for in the status line
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Prompt {
+    Filter,
+    Status,
+    Priority,
+    Assign,
+    Child,
+}
+
+/// Actions the caller carries out after a key press
+#[derive(PartialEq, Debug)]
+pub enum Action {
+    None,
+    Quit,
+    Reload,
+    Describe,
+    /// a prompt was requested and has to be opened with View::prompt
+    Prompt(Prompt),
+    /// a prompt was confirmed with the given input
+    Input(Prompt, String),
+}
+
+/// Terminal in raw mode showing an alternate screen, restored when dropped
+pub struct Terminal {
+    out: Stdout,
+    active: bool,
+}
+
+impl Terminal {
+    pub fn enter() -> Result<Terminal, String> {
+        let mut terminal = Terminal {
+            out: stdout(),
+            active: false,
+        };
+        terminal.resume()?;
+        Ok(terminal)
+    }
+
+    /// Restores the terminal, e.g. while an editor runs
+    pub fn suspend(&mut self) -> Result<(), String> {
+        if !self.active {
+            return Ok(());
+        }
+        self.active = false;
+        execute!(self.out, Show, LeaveAlternateScreen).map_err(|e| e.to_string())?;
+        terminal::disable_raw_mode().map_err(|e| e.to_string())
+    }
+
+    pub fn resume(&mut self) -> Result<(), String> {
+        if self.active {
+            return Ok(());
+        }
+        terminal::enable_raw_mode().map_err(|e| e.to_string())?;
+        self.active = true;
+        execute!(self.out, EnterAlternateScreen, Hide).map_err(|e| e.to_string())
+    }
+
+    /// Waits for the next key press, other events like resizes return None to redraw
+    pub fn key(&mut self) -> Result<Option<KeyEvent>, String> {
+        match event::read().map_err(|e| e.to_string())? {
+            Event::Key(v) => Ok(Some(v)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Draws header, issue list with the selected row, detail pane and status line
+    pub fn draw(
+        &mut self,
+        view: &mut View,
+        header: &str,
+        rows: &[String],
+        detail: &[String],
+    ) -> Result<(), String> {
+        let (width, height) = terminal::size().map_err(|e| e.to_string())?;
+        let (width, height) = (width as usize, height as usize);
+        let body = height.saturating_sub(2);
+        let list_width = (width * 2 / 5).max(MIN_LIST_WIDTH).min(width);
+        let row_width = list_width.saturating_sub(1);
+        let detail_width = width.saturating_sub(list_width + 1);
+        view.scroll(body, detail.len());
+
+        let sty_header = Style::new(Color::Default).invert();
+        let sty_selected = Style::new(Color::Default).invert();
+        let sty_border = Style::new(Color::Default).dimmed();
+
+        let mut lines = vec![sty_header.paint(fit(header, width)).to_string()];
+        for i in 0..body {
+            let index = view.offset + i;
+            let row = match rows.get(index) {
+                Some(v) if index == view.selected => {
+                    sty_selected.paint(fit(v, row_width)).to_string()
+                }
+                Some(v) => fit(v, row_width),
+                None => fit("", row_width),
+            };
+            let line = detail
+                .get(view.detail_offset + i)
+                .map(|v| fit(v, detail_width))
+                .unwrap_or_default();
+            lines.push(format!("{} {} {}", row, sty_border.paint("│"), line));
+        }
+        let status = match (&view.prompt, &view.message) {
+            (Some((_, label, input)), _) => format!("{}: {}_", label, input),
+            (None, Some(v)) => v.to_string(),
+            (None, None) => HELP.to_string(),
+        };
+        lines.push(fit(&status, width));
+
+        for (y, line) in lines.iter().enumerate().take(height) {
+            queue!(
+                self.out,
+                MoveTo(0, y as u16),
+                Print(line),
+                Clear(ClearType::UntilNewLine)
+            )
+            .map_err(|e| e.to_string())?;
+        }
+        self.out.flush().map_err(|e| e.to_string())
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        let _ = self.suspend();
+    }
+}
+
+/// Selection, scroll positions, filter and status line of the interactive mode
+#[derive(Default)]
+pub struct View {
+    pub filter: String,
+    selected: usize,
+    offset: usize,
+    detail_offset: usize,
+    page: usize,
+    prompt: Option<(Prompt, String, String)>,
+    message: Option<String>,
+}
+
+impl View {
+    pub fn selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects a row, clamped to the number of rows
+    pub fn select(&mut self, index: usize, count: usize) {
+        let index = index.min(count.saturating_sub(1));
+        if index != self.selected {
+            self.detail_offset = 0;
+        }
+        self.selected = index;
+    }
+
+    /// Shows a message in the status line until the next key press
+    pub fn message(&mut self, message: &str) {
+        self.message = Some(message.lines().collect::<Vec<_>>().join(" "));
+    }
+
+    /// Asks for input in the status line, label may list the accepted values
+    pub fn prompt(&mut self, prompt: Prompt, label: &str, input: &str) {
+        self.prompt = Some((prompt, label.to_string(), input.to_string()));
+    }
+
+    /// Keeps the selected row visible in a list of the given height
+    fn scroll(&mut self, height: usize, detail: usize) {
+        self.page = height.max(1);
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + self.page {
+            self.offset = self.selected + 1 - self.page;
+        }
+        self.detail_offset = self.detail_offset.min(detail.saturating_sub(1));
+    }
+
+    pub fn handle(&mut self, key: KeyEvent, count: usize) -> Action {
+        self.message = None;
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return Action::Quit;
+        }
+
+        // status line input
+        if let Some((prompt, label, mut input)) = self.prompt.take() {
+            match key.code {
+                KeyCode::Enter => return Action::Input(prompt, input),
+                KeyCode::Esc => return Action::None,
+                KeyCode::Backspace => {
+                    input.pop();
+                }
+                KeyCode::Char(c) => input.push(c),
+                _ => {}
+            }
+            self.prompt = Some((prompt, label, input));
+            return Action::None;
+        }
+
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Action::Quit,
+            KeyCode::Char('j') | KeyCode::Down => self.select(self.selected + 1, count),
+            KeyCode::Char('k') | KeyCode::Up => self.select(self.selected.saturating_sub(1), count),
+            KeyCode::PageDown => self.select(self.selected + self.page, count),
+            KeyCode::PageUp => self.select(self.selected.saturating_sub(self.page), count),
+            KeyCode::Char('g') | KeyCode::Home => self.select(0, count),
+            KeyCode::Char('G') | KeyCode::End => self.select(count.saturating_sub(1), count),
+            KeyCode::Char('J') => self.detail_offset += 1,
+            KeyCode::Char('K') => self.detail_offset = self.detail_offset.saturating_sub(1),
+            KeyCode::Char('/') => {
+                let filter = self.filter.clone();
+                self.prompt(Prompt::Filter, "filter query", &filter);
+            }
+            KeyCode::Char('s') if count > 0 => return Action::Prompt(Prompt::Status),
+            KeyCode::Char('p') if count > 0 => return Action::Prompt(Prompt::Priority),
+            KeyCode::Char('a') if count > 0 => return Action::Prompt(Prompt::Assign),
+            KeyCode::Char('c') if count > 0 => return Action::Prompt(Prompt::Child),
+            KeyCode::Char('e') if count > 0 => return Action::Describe,
+            KeyCode::Char('r') => return Action::Reload,
+            KeyCode::Char('?') => self.message(HELP),
+            _ => {}
+        }
+        Action::None
+    }
+}
+
+/// Cuts or pads text to exactly width visible characters, escape sequences are kept
+fn fit(text: &str, width: usize) -> String {
+    let mut fitted = String::new();
+    let mut visible = 0;
+    let mut escaped = false;
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            // copy the whole sequence, e.g. \x1b[1;31m
+            escaped = true;
+            fitted.push(c);
+            for c in chars.by_ref() {
+                fitted.push(c);
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+            continue;
+        }
+        if visible == width {
+            break;
+        }
+        fitted.push(if c == '\t' { ' ' } else { c });
+        visible += 1;
+    }
+    if escaped {
+        fitted.push_str("\x1b[0m");
+    }
+    fitted.push_str(&" ".repeat(width - visible));
+    fitted
+}
+
+/// Row of the issue list
+pub fn row(issue: &Issue, abbrev: &str) -> String {
+    format!(
+        "{} {:<11} {:<8} {}",
+        abbrev,
+        issue.status().to_string(),
+        issue.priority().to_string(),
+        issue.title()
+    )
+}
@@ -0,0 +1,127 @@
+use std::str::FromStr;
+
+use super::fields::Field;
+use super::{Config, Issue, IssueStatus};
+
+/// Checks the status change of an issue against the transitions of the tracker config.
+/// Without configured transitions every status change is allowed.
+pub fn check(
+    config: &Config,
+    from: &IssueStatus,
+    issue: &Issue,
+    subcommand: &str,
+) -> Result<(), String> {
+    check_pending(config, from, issue, subcommand, &[])
+}
+
+/// Checks the status change like check, required fields in pending count as set, e.g. a
+/// description which is edited after the check
+pub fn check_pending(
+    config: &Config,
+    from: &IssueStatus,
+    issue: &Issue,
+    subcommand: &str,
+    pending: &[&str],
+) -> Result<(), String> {
+    let to = issue.status();
+    if from == to || config.transitions().is_empty() {
+        return Ok(());
+    }
+
+    let transitions: Vec<_> = config
+        .transitions()
+        .iter()
+        .filter(|t| t.from() == from && t.to() == to)
+        .collect();
+    if transitions.is_empty() {
+        return Err(format!(
+            "status transition {} -> {} is not allowed, permitted next states: {}",
+            from,
+            to,
+            states(&next_states(config, from, subcommand))
+        ));
+    }
+
+    let transition = match transitions.iter().find(|t| match *t.subcommand() {
+        Some(ref v) => v == subcommand,
+        None => true,
+    }) {
+        Some(v) => v,
+        None => {
+            let subcommands: Vec<&str> = transitions
+                .iter()
+                .filter_map(|t| t.subcommand().as_ref().map(|v| v.as_str()))
+                .collect();
+            return Err(format!(
+                "status transition {} -> {} is only allowed via {}",
+                from,
+                to,
+                subcommands.join(", ")
+            ));
+        }
+    };
+
+    let mut missing = Vec::new();
+    for name in transition.required_fields() {
+        let pending = pending.iter().any(|v| v.eq_ignore_ascii_case(name.trim()));
+        if !pending && !is_set(name, issue) {
+            missing.push(name.to_string());
+        }
+    }
+    if !missing.is_empty() {
+        return Err(format!(
+            "status transition {} -> {} requires {}",
+            from,
+            to,
+            missing.join(", ")
+        ));
+    }
+
+    Ok(())
+}
+
+/// Returns whether the issue attribute or custom field of a required field holds a value.
+/// Attributes without an unset state like status or progress are always set.
+fn is_set(name: &str, issue: &Issue) -> bool {
+    match name.trim().to_lowercase().as_ref() {
+        "description" => match *issue.description() {
+            Some(ref v) => !v.trim().is_empty(),
+            None => false,
+        },
+        "estimate" => issue.estimate().is_some(),
+        "milestone" => issue.milestone().is_some(),
+        "comments" => !issue.comments().is_empty(),
+        "commits" => !issue.commits().is_empty(),
+        "worklog" | "work_logs" => !issue.work_logs().is_empty(),
+        "links" => !issue.links().is_empty(),
+        "attachments" => !issue.attachments().is_empty(),
+        _ => match Field::from_str(name) {
+            Ok(field) => field.is_set(issue),
+            // custom fields declared in the tracker config
+            Err(_) => issue.custom_fields().contains_key(name),
+        },
+    }
+}
+
+/// Returns the states reachable from the given status with the given subcommand
+pub fn next_states(config: &Config, from: &IssueStatus, subcommand: &str) -> Vec<IssueStatus> {
+    let mut states: Vec<IssueStatus> = Vec::new();
+    for transition in config.transitions() {
+        let permitted = match *transition.subcommand() {
+            Some(ref v) => v == subcommand,
+            None => true,
+        };
+        if transition.from() == from && permitted && !states.contains(transition.to()) {
+            states.push(transition.to().to_owned());
+        }
+    }
+    states
+}
+
+fn states(states: &[IssueStatus]) -> String {
+    if states.is_empty() {
+        return "none".to_string();
+    }
+    let states: Vec<String> = states.iter().map(|v| v.to_string()).collect();
+    states.join(", ")
+}
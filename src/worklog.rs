@@ -0,0 +1,170 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde_json::{json, Value};
+
+/// Name of the file in the tracker directory holding the running timers
+const TIMERS_FILE: &str = "timers.json";
+
+/// Seconds of a logged day, work days are counted with 8 hours
+const DAY: u64 = 8 * 60 * 60;
+
+/// Parses durations like '1h30m', '45m', '2h', '1d' or '0', a day counts 8 hours
+pub fn parse(value: &str) -> Result<u64, String> {
+    let value = value.trim().to_lowercase();
+    let invalid = || format!("invalid duration '{}', expected e.g. 1h30m", value);
+    // zero needs no unit
+    if !value.is_empty() && value.chars().all(|c| c == '0') {
+        return Ok(0);
+    }
+
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+    for c in value.chars() {
+        match c {
+            '0'..='9' => number.push(c),
+            'd' | 'h' | 'm' | 's' if !number.is_empty() => {
+                let unit = match c {
+                    'd' => DAY,
+                    'h' => 60 * 60,
+                    'm' => 60,
+                    _ => 1,
+                };
+                seconds = number
+                    .parse::<u64>()
+                    .ok()
+                    .and_then(|n| n.checked_mul(unit))
+                    .and_then(|v| v.checked_add(seconds))
+                    .ok_or_else(|| format!("duration '{}' is too long", value))?;
+                number.clear();
+            }
+            _ => return Err(invalid()),
+        }
+    }
+    if !number.is_empty() || value.is_empty() {
+        return Err(invalid());
+    }
+    Ok(seconds)
+}
+
+/// Formats seconds as hours and minutes, e.g. '1h30m'
+pub fn format(seconds: u64) -> String {
+    let minutes = seconds.div_ceil(60);
+    match (minutes / 60, minutes % 60) {
+        (0, m) => format!("{}m", m),
+        (h, 0) => format!("{}h", h),
+        (h, m) => format!("{}h{}m", h, m),
+    }
+}
+
+/// Logged and estimated seconds of an issue, the totals include all of its descendants
+#[derive(Default)]
+pub struct TimeTotals {
+    pub logged: u64,
+    pub estimate: u64,
+    pub total_logged: u64,
+    pub total_estimate: u64,
+}
+
+/// Running timer of a user
+pub struct Timer {
+    pub hash: String,
+    pub started: i64,
+}
+
+/// Reads the running timers of all users from the tracker directory
+pub fn timers(tracker_dir: &Path) -> Result<BTreeMap<String, Timer>, String> {
+    let path = tracker_dir.join(TIMERS_FILE);
+    let data = match fs::read_to_string(&path) {
+        Ok(v) => v,
+        Err(ref e) if e.kind() == io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+        Err(e) => return Err(format!("{}: {}", path.display(), e)),
+    };
+    let value: Value =
+        serde_json::from_str(&data).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+    let mut timers = BTreeMap::new();
+    if let Some(users) = value.as_object() {
+        for (user, timer) in users {
+            let hash = timer["hash"].as_str();
+            let started = timer["started"].as_i64();
+            if let (Some(hash), Some(started)) = (hash, started) {
+                let timer = Timer {
+                    hash: hash.to_string(),
+                    started,
+                };
+                timers.insert(user.to_string(), timer);
+            }
+        }
+    }
+    Ok(timers)
+}
+
+/// Writes the running timers, the file is removed when no timer is running
+pub fn write_timers(tracker_dir: &Path, timers: &BTreeMap<String, Timer>) -> Result<(), String> {
+    let path = tracker_dir.join(TIMERS_FILE);
+    if timers.is_empty() {
+        return match fs::remove_file(&path) {
+            Err(ref e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(format!("{}: {}", path.display(), e))
+            }
+            _ => Ok(()),
+        };
+    }
+
+    let mut value = serde_json::Map::new();
+    for (user, timer) in timers {
+        value.insert(
+            user.to_string(),
+            json!({ "hash": timer.hash, "started": timer.started }),
+        );
+    }
+    let data = serde_json::to_string_pretty(&Value::Object(value)).map_err(|e| e.to_string())?;
+    fs::write(&path, data).map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_units() {
+        assert_eq!(parse("45m"), Ok(45 * 60));
+        assert_eq!(parse("1h30m"), Ok(90 * 60));
+        assert_eq!(parse(" 2H "), Ok(2 * 60 * 60));
+        assert_eq!(parse("1d"), Ok(DAY));
+        assert_eq!(parse("1d1s"), Ok(DAY + 1));
+    }
+
+    #[test]
+    fn parse_zero() {
+        assert_eq!(parse("0"), Ok(0));
+        assert_eq!(parse("0m"), Ok(0));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("5").is_err());
+        assert!(parse("h").is_err());
+        assert!(parse("1x").is_err());
+        assert!(parse("1h30").is_err());
+    }
+
+    #[test]
+    fn parse_overflow() {
+        assert!(parse("99999999999999999999d").is_err());
+        assert!(parse("18446744073709551615d").is_err());
+        assert!(parse("18446744073709551615s1s").is_err());
+    }
+
+    #[test]
+    fn format_rounds_up() {
+        assert_eq!(format(0), "0m");
+        assert_eq!(format(61), "2m");
+        assert_eq!(format(3600), "1h");
+        assert_eq!(format(5400), "1h30m");
+    }
+}